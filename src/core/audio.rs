@@ -3,7 +3,13 @@ use bevy::audio::AudioPlugin;
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.configure::<(AudioSettings, IsMusicAudio, IsUiAudio)>();
+    app.configure::<(
+        AudioSettings,
+        IsMusicAudio,
+        IsUiAudio,
+        IsSfxAudio,
+        VolumeScale,
+    )>();
 
     app.add_plugins(AudioPlugin::default());
 }
@@ -14,6 +20,7 @@ pub struct AudioSettings {
     pub master_volume: f32,
     pub music_volume: f32,
     pub ui_volume: f32,
+    pub sfx_volume: f32,
 }
 
 impl Default for AudioSettings {
@@ -22,6 +29,7 @@ impl Default for AudioSettings {
             master_volume: 0.5,
             music_volume: 0.5,
             ui_volume: 0.5,
+            sfx_volume: 0.5,
         }
     }
 }
@@ -47,6 +55,10 @@ impl AudioSettings {
     pub fn ui_volume(&self) -> Volume {
         Volume::Linear(self.master_volume * self.ui_volume)
     }
+
+    pub fn sfx_volume(&self) -> Volume {
+        Volume::Linear(self.master_volume * self.sfx_volume)
+    }
 }
 
 #[cfg_attr(feature = "native_dev", hot)]
@@ -54,6 +66,7 @@ fn apply_audio_settings(
     audio_settings: Res<AudioSettings>,
     music_audio_query: Query<Entity, With<IsMusicAudio>>,
     ui_audio_query: Query<Entity, With<IsUiAudio>>,
+    sfx_audio_query: Query<(Entity, Option<&VolumeScale>), With<IsSfxAudio>>,
     mut volume_query: Query<(Option<&mut PlaybackSettings>, Option<&mut AudioSink>)>,
 ) {
     // Apply music volume.
@@ -77,6 +90,18 @@ fn apply_audio_settings(
             playback.volume = volume;
         }
     }
+
+    // Apply SFX volume, keeping each sound's own scale.
+    let sfx_volume = audio_settings.sfx_volume();
+    for (entity, scale) in &sfx_audio_query {
+        let volume = scale.map_or(sfx_volume, |x| x.apply(sfx_volume));
+        let (playback, sink) = c!(volume_query.get_mut(entity));
+        if let Some(mut sink) = sink {
+            sink.set_volume(volume);
+        } else if let Some(mut playback) = playback {
+            playback.volume = volume;
+        }
+    }
 }
 
 pub fn music_audio(audio_settings: &AudioSettings, handle: Handle<AudioSource>) -> impl Bundle {
@@ -98,13 +123,17 @@ impl Configure for IsMusicAudio {
     }
 }
 
-pub fn ui_audio(audio_settings: &AudioSettings, handle: Handle<AudioSource>) -> impl Bundle {
+pub fn ui_audio(
+    audio_settings: &AudioSettings,
+    handle: Handle<AudioSource>,
+    rng: &mut impl Rng,
+) -> impl Bundle {
     (
         Name::new("UiAudio"),
         AudioPlayer(handle),
         PlaybackSettings::DESPAWN
             .with_volume(audio_settings.ui_volume())
-            .with_speed(thread_rng().gen_range(0.9..1.5)),
+            .with_speed(rng.gen_range(0.9..1.5)),
         IsUiAudio,
     )
 }
@@ -118,3 +147,36 @@ impl Configure for IsUiAudio {
         app.register_type::<Self>();
     }
 }
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct IsSfxAudio;
+
+impl Configure for IsSfxAudio {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Systems that play sound effects during gameplay.
+///
+/// Headless apps have no audio or camera, so they never run these.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SfxSystems;
+
+/// A factor applied on top of the volume setting for one sound, like a louder hit.
+#[derive(Component, Reflect, Copy, Clone, Debug)]
+#[reflect(Component)]
+pub struct VolumeScale(pub f32);
+
+impl Configure for VolumeScale {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+impl VolumeScale {
+    pub fn apply(self, volume: Volume) -> Volume {
+        Volume::Linear(volume.to_linear() * self.0)
+    }
+}
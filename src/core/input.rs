@@ -0,0 +1,304 @@
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<Controls>();
+}
+
+/// A rebindable control, shown as one row in the controls menu.
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ControlSlot {
+    MenuBack,
+    TogglePause,
+    SkipReplay,
+    Player1Up,
+    Player1Down,
+    Player2Up,
+    Player2Down,
+    Player3Left,
+    Player3Right,
+    Player4Left,
+    Player4Right,
+}
+
+impl ControlSlot {
+    pub const ALL: [Self; 11] = [
+        Self::MenuBack,
+        Self::TogglePause,
+        Self::SkipReplay,
+        Self::Player1Up,
+        Self::Player1Down,
+        Self::Player2Up,
+        Self::Player2Down,
+        Self::Player3Left,
+        Self::Player3Right,
+        Self::Player4Left,
+        Self::Player4Right,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MenuBack => "Menu back",
+            Self::TogglePause => "Pause",
+            Self::SkipReplay => "Skip replay",
+            Self::Player1Up => "P1 up",
+            Self::Player1Down => "P1 down",
+            Self::Player2Up => "P2 up",
+            Self::Player2Down => "P2 down",
+            Self::Player3Left => "P3 left",
+            Self::Player3Right => "P3 right",
+            Self::Player4Left => "P4 left",
+            Self::Player4Right => "P4 right",
+        }
+    }
+
+    fn group(self) -> ControlGroup {
+        match self {
+            Self::MenuBack => ControlGroup::Menu,
+            Self::TogglePause | Self::SkipReplay => ControlGroup::Gameplay,
+            Self::Player1Up | Self::Player1Down => ControlGroup::Player1,
+            Self::Player2Up | Self::Player2Down => ControlGroup::Player2,
+            Self::Player3Left | Self::Player3Right => ControlGroup::Player3,
+            Self::Player4Left | Self::Player4Right => ControlGroup::Player4,
+        }
+    }
+
+    /// Whether the two controls can't share a keyboard key.
+    fn key_conflicts_with(self, other: Self) -> bool {
+        let is_menu = |x: Self| x.group() == ControlGroup::Menu;
+        self != other && is_menu(self) == is_menu(other)
+    }
+
+    /// Whether the two controls can't share a gamepad button.
+    ///
+    /// Each player reads from their own gamepad, so only the controls of one player
+    /// and the controls that read from every gamepad can conflict.
+    fn button_conflicts_with(self, other: Self) -> bool {
+        use ControlGroup::*;
+
+        self != other
+            && match (self.group(), other.group()) {
+                (Menu, Menu) => true,
+                (Menu, _) | (_, Menu) => false,
+                (Gameplay, _) | (_, Gameplay) => true,
+                (a, b) => a == b,
+            }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum ControlGroup {
+    Menu,
+    Gameplay,
+    Player1,
+    Player2,
+    Player3,
+    Player4,
+}
+
+/// The keyboard keys and gamepad buttons bound to one control.
+#[derive(Reflect, Clone, Default, Debug)]
+pub struct Binding {
+    pub keys: Vec<KeyCode>,
+    pub buttons: Vec<GamepadButton>,
+}
+
+impl Binding {
+    fn new(keys: impl Into<Vec<KeyCode>>, buttons: impl Into<Vec<GamepadButton>>) -> Self {
+        Self {
+            keys: keys.into(),
+            buttons: buttons.into(),
+        }
+    }
+
+    /// Adds every key and button in this binding to `input_map` for `action`.
+    pub fn insert_into<A: Actionlike>(&self, input_map: &mut InputMap<A>, action: A) {
+        self.insert_keys_into(input_map, action.clone());
+        for &button in &self.buttons {
+            input_map.insert(action.clone(), button);
+        }
+    }
+
+    /// Adds only the keys in this binding to `input_map` for `action`.
+    pub fn insert_keys_into<A: Actionlike>(&self, input_map: &mut InputMap<A>, action: A) {
+        for &key in &self.keys {
+            input_map.insert(action.clone(), key);
+        }
+    }
+
+    pub fn keys_text(&self) -> String {
+        join_or_dash(self.keys.iter().map(|key| {
+            let name = format!("{key:?}");
+            name.strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .unwrap_or(&name)
+                .to_string()
+        }))
+    }
+
+    pub fn buttons_text(&self) -> String {
+        join_or_dash(self.buttons.iter().map(|button| format!("{button:?}")))
+    }
+}
+
+fn join_or_dash(names: impl Iterator<Item = String>) -> String {
+    let text = names.collect::<Vec<_>>().join(", ");
+    if text.is_empty() {
+        "-".to_string()
+    } else {
+        text
+    }
+}
+
+/// The bindings for every rebindable control.
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct Controls {
+    pub bindings: HashMap<ControlSlot, Binding>,
+}
+
+impl Configure for Controls {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::from([
+                (
+                    ControlSlot::MenuBack,
+                    Binding::new([KeyCode::Escape], [GamepadButton::South]),
+                ),
+                (
+                    ControlSlot::TogglePause,
+                    Binding::new([KeyCode::Escape, KeyCode::KeyP], [GamepadButton::Start]),
+                ),
+                (
+                    ControlSlot::SkipReplay,
+                    Binding::new([KeyCode::Space], [GamepadButton::East]),
+                ),
+                (
+                    ControlSlot::Player1Up,
+                    Binding::new([KeyCode::KeyW], [GamepadButton::DPadUp]),
+                ),
+                (
+                    ControlSlot::Player1Down,
+                    Binding::new([KeyCode::KeyS], [GamepadButton::DPadDown]),
+                ),
+                (
+                    ControlSlot::Player2Up,
+                    Binding::new([KeyCode::ArrowUp], [GamepadButton::DPadUp]),
+                ),
+                (
+                    ControlSlot::Player2Down,
+                    Binding::new([KeyCode::ArrowDown], [GamepadButton::DPadDown]),
+                ),
+                (
+                    ControlSlot::Player3Left,
+                    Binding::new([KeyCode::KeyJ], [GamepadButton::DPadLeft]),
+                ),
+                (
+                    ControlSlot::Player3Right,
+                    Binding::new([KeyCode::KeyL], [GamepadButton::DPadRight]),
+                ),
+                (
+                    ControlSlot::Player4Left,
+                    Binding::new([KeyCode::Numpad4], [GamepadButton::DPadLeft]),
+                ),
+                (
+                    ControlSlot::Player4Right,
+                    Binding::new([KeyCode::Numpad6], [GamepadButton::DPadRight]),
+                ),
+            ]),
+        }
+    }
+}
+
+impl Controls {
+    pub fn get(&self, slot: ControlSlot) -> Binding {
+        self.bindings.get(&slot).cloned().unwrap_or_default()
+    }
+
+    /// Binds `key` to `slot`, replacing its previous keys.
+    ///
+    /// If another control was using `key`, it receives `slot`'s previous keys instead,
+    /// and is returned.
+    pub fn rebind_key(&mut self, slot: ControlSlot, key: KeyCode) -> Option<ControlSlot> {
+        self.rebind(slot, key, ControlSlot::key_conflicts_with, |x| &mut x.keys)
+    }
+
+    /// Binds `button` to `slot`, replacing its previous buttons.
+    ///
+    /// If another control was using `button`, it receives `slot`'s previous buttons
+    /// instead, and is returned.
+    pub fn rebind_button(
+        &mut self,
+        slot: ControlSlot,
+        button: GamepadButton,
+    ) -> Option<ControlSlot> {
+        self.rebind(slot, button, ControlSlot::button_conflicts_with, |x| {
+            &mut x.buttons
+        })
+    }
+
+    fn rebind<T: Copy + PartialEq>(
+        &mut self,
+        slot: ControlSlot,
+        input: T,
+        conflicts: fn(ControlSlot, ControlSlot) -> bool,
+        inputs: fn(&mut Binding) -> &mut Vec<T>,
+    ) -> Option<ControlSlot> {
+        let old = std::mem::replace(inputs(self.bindings.entry(slot).or_default()), vec![input]);
+
+        // Swap with the conflicting control, if any.
+        let conflict = ControlSlot::ALL.into_iter().find(|&other| {
+            conflicts(slot, other)
+                && self
+                    .bindings
+                    .get_mut(&other)
+                    .is_some_and(|x| inputs(x).contains(&input))
+        })?;
+        let conflict_inputs = inputs(self.bindings.entry(conflict).or_default());
+        conflict_inputs.retain(|&x| x != input);
+        for x in old {
+            if x != input && !conflict_inputs.contains(&x) {
+                conflict_inputs.push(x);
+            }
+        }
+
+        Some(conflict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebind_swaps_conflicts() {
+        let mut controls = Controls::default();
+
+        // Player 1 takes player 2's key, so player 2 gets player 1's old key.
+        let conflict = controls.rebind_key(ControlSlot::Player1Up, KeyCode::ArrowUp);
+        assert_eq!(conflict, Some(ControlSlot::Player2Up));
+        assert_eq!(
+            controls.get(ControlSlot::Player1Up).keys,
+            [KeyCode::ArrowUp]
+        );
+        assert_eq!(controls.get(ControlSlot::Player2Up).keys, [KeyCode::KeyW]);
+
+        // Menu and gameplay controls don't conflict.
+        let conflict = controls.rebind_key(ControlSlot::MenuBack, KeyCode::KeyP);
+        assert_eq!(conflict, None);
+
+        // Each player has their own gamepad.
+        let conflict = controls.rebind_button(ControlSlot::Player1Up, GamepadButton::DPadDown);
+        assert_eq!(conflict, Some(ControlSlot::Player1Down));
+        assert_eq!(
+            controls.get(ControlSlot::Player2Down).buttons,
+            [GamepadButton::DPadDown],
+        );
+    }
+}
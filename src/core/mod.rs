@@ -5,8 +5,10 @@ pub mod audio;
 pub mod camera;
 #[cfg(feature = "dev")]
 pub mod dev;
+pub mod input;
 pub mod pause;
 pub mod physics;
+pub mod rng;
 pub mod state;
 pub mod window;
 
@@ -35,12 +37,48 @@ pub(super) fn plugin(app: &mut App) {
         camera::plugin,
         #[cfg(feature = "dev")]
         dev::plugin,
+        input::plugin,
         pause::plugin,
         physics::plugin,
+        rng::plugin,
     ));
 }
 
-/// Game logic steps for the [`Update`] schedule.
+/// Core plugins for simulating gameplay without a window, rendering, or audio.
+///
+/// Time advances by exactly one fixed timestep per update, so physics steps once per update.
+#[cfg(any(test, feature = "headless"))]
+pub fn headless_plugin(app: &mut App) {
+    use bevy::input::InputPlugin;
+    use bevy::state::app::StatesPlugin;
+    use bevy::time::TimeUpdateStrategy;
+
+    app.configure::<UpdateSystems>();
+
+    // Add Bevy plugins.
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        InputPlugin,
+        TransformPlugin,
+        StatesPlugin,
+        state::plugin,
+    ));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        Time::<Fixed>::default().timestep(),
+    ));
+
+    // Add other core plugins.
+    app.add_plugins((input::plugin, pause::plugin, physics::plugin, rng::plugin));
+
+    // There's no audio or camera to play sound effects with.
+    app.configure_sets(StateFlush, audio::SfxSystems.run_if(|| false));
+    app.configure_sets(Update, audio::SfxSystems.run_if(|| false));
+}
+
+/// Game logic steps for the [`Update`] and [`FixedUpdate`] schedules.
+///
+/// Gameplay runs in [`FixedUpdate`] so that it steps the same way regardless of frame rate.
 #[derive(SystemSet, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum UpdateSystems {
     /// Synchronize start-of-frame values.
@@ -61,8 +99,7 @@ pub enum UpdateSystems {
 
 impl Configure for UpdateSystems {
     fn configure(app: &mut App) {
-        app.configure_sets(
-            Update,
+        let sets = || {
             (
                 Self::SyncEarly,
                 Self::TickTimers,
@@ -72,7 +109,9 @@ impl Configure for UpdateSystems {
                 Self::ApplyCommands,
                 Self::SyncLate,
             )
-                .chain(),
-        );
+                .chain()
+        };
+        app.configure_sets(Update, sets());
+        app.configure_sets(FixedUpdate, sets());
     }
 }
@@ -0,0 +1,82 @@
+use rand::rngs::SmallRng;
+
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(GameRng, UiRng)>();
+}
+
+/// The random number generator for gameplay.
+///
+/// This is reseeded at the start of each match, so the same seed with the same inputs
+/// always plays out the same way.
+#[derive(Resource, Clone)]
+pub struct GameRng {
+    seed: u64,
+    rng: SmallRng,
+}
+
+impl Configure for GameRng {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(random())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed this was last reset with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+}
+
+/// The random number generator for UI effects, kept apart from [`GameRng`] so that
+/// clicking around menus can't change how a match plays out.
+#[derive(Resource, Deref, DerefMut)]
+pub struct UiRng(pub SmallRng);
+
+impl Configure for UiRng {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+    }
+}
+
+impl Default for UiRng {
+    fn default() -> Self {
+        Self(SmallRng::from_entropy())
+    }
+}
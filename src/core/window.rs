@@ -10,7 +10,7 @@ pub(super) fn plugin(app: &mut App) {
         primary_window: Some(Window {
             fit_canvas_to_parent: false,
             visible: false,
-            resizable: false,
+            resizable: true,
             resolution: WindowResolution::new(1000.0, 600.0),
             ..default()
         }),
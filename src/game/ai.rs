@@ -0,0 +1,196 @@
+use crate::core::rng::GameRng;
+use crate::game::Ball;
+use crate::game::Side;
+use crate::game::arena::ArenaConfig;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<CpuController>();
+}
+
+#[derive(Reflect, Copy, Clone, Default, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum CpuDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl CpuDifficulty {
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+        }
+    }
+
+    /// Seconds between re-reading the ball's position.
+    pub fn reaction_delay(self) -> f32 {
+        match self {
+            Self::Easy => 0.35,
+            Self::Normal => 0.2,
+            Self::Hard => 0.08,
+        }
+    }
+
+    /// Maximum paddle speed in pixels per second.
+    pub fn max_speed(self) -> f32 {
+        match self {
+            Self::Easy => 250.0,
+            Self::Normal => 380.0,
+            Self::Hard => 520.0,
+        }
+    }
+
+    /// Maximum error in pixels when predicting where the ball will arrive.
+    pub fn prediction_error(self) -> f32 {
+        match self {
+            Self::Easy => 90.0,
+            Self::Normal => 45.0,
+            Self::Hard => 12.0,
+        }
+    }
+}
+
+/// Moves a paddle toward where it expects the [`Ball`] to arrive.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct CpuController {
+    pub difficulty: CpuDifficulty,
+    reaction: Timer,
+    /// Where to move the paddle along the axis it slides on.
+    target: f32,
+}
+
+impl Configure for CpuController {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update(
+                (update_cpu_target, move_cpu_paddle)
+                    .chain()
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+    }
+}
+
+impl CpuController {
+    pub fn new(difficulty: CpuDifficulty) -> Self {
+        Self {
+            difficulty,
+            reaction: Timer::from_seconds(difficulty.reaction_delay(), TimerMode::Repeating),
+            target: 0.0,
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_cpu_target(
+    time: Res<Time>,
+    config: ConfigRef<ArenaConfig>,
+    mut rng: ResMut<GameRng>,
+    ball_query: Query<(&Transform, &LinearVelocity, &Collider), With<Ball>>,
+    mut cpu_query: Query<(&mut CpuController, &Side, &Transform), Without<Ball>>,
+) {
+    let arena_size = r!(config.get()).arena_size;
+
+    for (mut cpu, &side, transform) in &mut cpu_query {
+        cq!(cpu.reaction.tick(time.delta()).just_finished());
+
+        // Paddles on the top and bottom predict the same way with the axes swapped.
+        let swap = |v: Vec2| if side.is_horizontal() { v.yx() } else { v };
+        let half_height = swap(arena_size).y / 2.0;
+
+        // Follow whichever approaching ball will arrive first.
+        let paddle_x = swap(transform.translation.xy()).x;
+        let predicted = ball_query
+            .iter()
+            .filter_map(|(ball_transform, ball_velocity, ball_collider)| {
+                let ball_pos = swap(ball_transform.translation.xy());
+                let ball_velocity = swap(ball_velocity.0);
+                let approaching = (paddle_x - ball_pos.x) * ball_velocity.x > 0.0;
+                if !approaching {
+                    return None;
+                }
+
+                let ball_radius = ball_collider
+                    .shape()
+                    .as_ball()
+                    .map_or(0.0, |ball| ball.radius);
+                let y = predict_y(ball_pos, ball_velocity, paddle_x, half_height - ball_radius)?;
+                Some(((paddle_x - ball_pos.x) / ball_velocity.x, y))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, y)| y);
+
+        let error = cpu.difficulty.prediction_error();
+        cpu.target = predicted.unwrap_or(0.0) + rng.gen_range(-error..=error);
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn move_cpu_paddle(mut cpu_query: Query<(&CpuController, &Side, &Transform, &mut LinearVelocity)>) {
+    for (cpu, side, transform, mut velocity) in &mut cpu_query {
+        let max_speed = cpu.difficulty.max_speed();
+        if side.is_horizontal() {
+            let dx = cpu.target - transform.translation.x;
+            velocity.x = (dx * 10.0).clamp(-max_speed, max_speed);
+        } else {
+            let dy = cpu.target - transform.translation.y;
+            velocity.y = (dy * 10.0).clamp(-max_speed, max_speed);
+        }
+    }
+}
+
+/// Predicts the y position where a ball will cross `target_x`, bouncing off walls at
+/// `±half_height`.
+fn predict_y(pos: Vec2, velocity: Vec2, target_x: f32, half_height: f32) -> Option<f32> {
+    if velocity.x.abs() <= f32::EPSILON || half_height <= 0.0 {
+        return None;
+    }
+    let t = (target_x - pos.x) / velocity.x;
+    if t < 0.0 {
+        return None;
+    }
+
+    // Unfold the reflections by wrapping into a range of twice the arena height.
+    let y = pos.y + velocity.y * t;
+    let span = 2.0 * half_height;
+    let wrapped = (y + half_height).rem_euclid(2.0 * span);
+    Some(if wrapped <= span {
+        wrapped - half_height
+    } else {
+        3.0 * half_height - wrapped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predict_y() {
+        // Straight line, no bounce.
+        let y = predict_y(vec2(0.0, 0.0), vec2(100.0, 50.0), 100.0, 300.0);
+        assert_eq!(y, Some(50.0));
+
+        // One bounce off the top wall.
+        let y = predict_y(vec2(0.0, 0.0), vec2(100.0, 400.0), 100.0, 300.0);
+        assert_eq!(y, Some(200.0));
+
+        // One bounce off the bottom wall.
+        let y = predict_y(vec2(0.0, 0.0), vec2(100.0, -400.0), 100.0, 300.0);
+        assert_eq!(y, Some(-200.0));
+
+        // Moving away from the target.
+        let y = predict_y(vec2(0.0, 0.0), vec2(-100.0, 0.0), 100.0, 300.0);
+        assert_eq!(y, None);
+    }
+}
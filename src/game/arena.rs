@@ -0,0 +1,150 @@
+use bevy::render::camera::ScalingMode;
+
+use crate::core::camera::CameraRoot;
+use crate::game::Ball;
+use crate::game::Boundary;
+use crate::game::Player;
+use crate::game::Side;
+use crate::game::Slot;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<ConfigHandle<ArenaConfig>>();
+}
+
+/// The size and placement of everything in the arena.
+#[derive(Asset, Reflect, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ArenaConfig {
+    /// The playfield width and height in world units, inside the walls.
+    /// The camera scales this to fit the window, whatever its size.
+    pub arena_size: Vec2,
+    /// The paddle width and height in world units, standing upright on the left and right.
+    pub paddle_size: Vec2,
+    /// The distance in world units from the center of the arena to the left and right paddles.
+    /// The top and bottom paddles in free-for-all matches keep the same distance from their goals.
+    pub paddle_distance: f32,
+    /// The distance in world units from the center of the arena to the front paddles in
+    /// doubles matches, on the same side as their team's goal.
+    pub front_paddle_distance: f32,
+    /// The human paddle speed, multiplied by the fixed timestep to get world units per second.
+    pub paddle_speed_factor: f32,
+    /// The ball radius in world units.
+    pub ball_radius: f32,
+    /// The thickness of the arena walls in world units.
+    pub boundary_width: f32,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        Self {
+            arena_size: vec2(1000.0, 600.0),
+            paddle_size: vec2(20.0, 200.0),
+            paddle_distance: 400.0,
+            front_paddle_distance: 150.0,
+            paddle_speed_factor: 25000.0,
+            ball_radius: 10.0,
+            boundary_width: 20.0,
+        }
+    }
+}
+
+impl Config for ArenaConfig {
+    const FILE: &'static str = "arena.ron";
+
+    fn on_load(&self, world: &mut World) {
+        r!(world.run_system_cached(resize_arena));
+    }
+}
+
+impl ArenaConfig {
+    /// The half-height of the playfield inside the walls.
+    pub fn half_height(&self) -> f32 {
+        self.arena_size.y / 2.0
+    }
+
+    /// The area the camera must always show: the playfield and its walls.
+    pub fn view_size(&self) -> Vec2 {
+        self.arena_size + 2.0 * self.boundary_width
+    }
+
+    /// The starting position of a paddle on the given side, in the given row.
+    pub fn paddle_position(&self, side: Side, slot: Slot) -> Vec2 {
+        let distance = match slot {
+            Slot::Back => self.paddle_distance,
+            Slot::Front => self.front_paddle_distance,
+        };
+        let goal_gap = self.arena_size.x / 2.0 - distance;
+        match side {
+            Side::Left => vec2(-distance, 0.0),
+            Side::Right => vec2(distance, 0.0),
+            Side::Top => vec2(0.0, self.half_height() - goal_gap),
+            Side::Bottom => vec2(0.0, goal_gap - self.half_height()),
+        }
+    }
+
+    /// The size of the paddle defending the given side, lying flat on the top and bottom.
+    pub fn paddle_size(&self, side: Side) -> Vec2 {
+        if side.is_horizontal() {
+            self.paddle_size.yx()
+        } else {
+            self.paddle_size
+        }
+    }
+}
+
+/// Apply the current config to the camera and to an arena that has already been spawned.
+fn resize_arena(
+    config: ConfigRef<ArenaConfig>,
+    camera_root: Res<CameraRoot>,
+    mut projection_query: Query<&mut Projection>,
+    mut paddle_query: Query<
+        (&Side, &Slot, &mut Transform, &mut Collider, &mut Sprite),
+        (With<Player>, Without<Ball>, Without<Boundary>),
+    >,
+    mut ball_query: Query<
+        (&mut Collider, &mut Sprite),
+        (With<Ball>, Without<Player>, Without<Boundary>),
+    >,
+    mut boundary_query: Query<
+        (&Boundary, &mut Transform, &mut Collider, &mut Sprite),
+        (Without<Player>, Without<Ball>),
+    >,
+) {
+    let config = r!(config.get());
+
+    // Scale the arena to fit the window, letterboxed by the background color.
+    if let Projection::Orthographic(projection) =
+        &mut *r!(projection_query.get_mut(camera_root.primary))
+    {
+        let view_size = config.view_size();
+        projection.scaling_mode = ScalingMode::AutoMin {
+            min_width: view_size.x,
+            min_height: view_size.y,
+        };
+    }
+
+    for (&side, &slot, mut transform, mut collider, mut sprite) in &mut paddle_query {
+        let position = config.paddle_position(side, slot);
+        if side.is_horizontal() {
+            transform.translation.y = position.y;
+        } else {
+            transform.translation.x = position.x;
+        }
+        let size = config.paddle_size(side);
+        *collider = Collider::rectangle(size.x, size.y);
+        sprite.custom_size = Some(size);
+    }
+
+    for (mut collider, mut sprite) in &mut ball_query {
+        *collider = Collider::circle(config.ball_radius);
+        sprite.custom_size = Some(Vec2::splat(config.ball_radius * 2.0));
+    }
+
+    for (&boundary, mut transform, mut collider, mut sprite) in &mut boundary_query {
+        let rect = boundary.rect(config.arena_size, config.boundary_width);
+        transform.translation = rect.center().extend(transform.translation.z);
+        *collider = Collider::rectangle(rect.width(), rect.height());
+        sprite.custom_size = Some(rect.size());
+    }
+}
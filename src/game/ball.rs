@@ -0,0 +1,271 @@
+use avian2d::math::Vector;
+
+use crate::core::rng::GameRng;
+use crate::game::Ball;
+use crate::game::Player;
+use crate::game::Side;
+use crate::game::arena::ArenaConfig;
+use crate::game::ball_bundle;
+use crate::game::event::PaddleHit;
+use crate::game::event::ServeLaunched;
+use crate::game::mode::MatchSetup;
+use crate::game::powerup::PowerUpConfig;
+use crate::game::powerup::PowerUps;
+use crate::game::rules::MatchConfig;
+use crate::game::serve::Serve;
+use crate::game::serve::serve_direction;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<BallConfig>, Rally)>();
+
+    app.add_observer(deflect_off_paddle);
+}
+
+/// Tuning for how the ball moves.
+#[derive(Asset, Reflect, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct BallConfig {
+    /// The ball speed in pixels per second at the start of each rally.
+    pub base_speed: f32,
+    /// How much the ball speeds up with each paddle hit in a rally.
+    pub speed_up_per_hit: f32,
+    /// The fastest the ball can go, no matter how long the rally.
+    pub max_speed: f32,
+    /// The smallest fraction of the ball's speed that must be horizontal,
+    /// so the ball can't get stuck bouncing between the top and bottom walls.
+    pub min_horizontal_ratio: f32,
+    /// The outgoing angle in degrees away from horizontal when the ball hits the
+    /// very edge of a paddle.
+    pub max_deflection_angle: f32,
+    /// The paddle hits in a rally between extra balls joining, in multi-ball matches.
+    pub extra_ball_hits: u32,
+    /// The most balls that can be in play at once, in multi-ball matches.
+    pub max_balls: usize,
+}
+
+impl Default for BallConfig {
+    fn default() -> Self {
+        Self {
+            base_speed: 250.0,
+            speed_up_per_hit: 15.0,
+            max_speed: 700.0,
+            min_horizontal_ratio: 0.5,
+            max_deflection_angle: 60.0,
+            extra_ball_hits: 4,
+            max_balls: 4,
+        }
+    }
+}
+
+impl Config for BallConfig {
+    const FILE: &'static str = "ball.ron";
+}
+
+impl BallConfig {
+    /// The ball speed after a number of paddle hits in a rally.
+    pub fn rally_speed(&self, hits: u32) -> f32 {
+        let speed = self.base_speed + hits as f32 * self.speed_up_per_hit;
+        speed.min(self.max_speed.max(self.base_speed))
+    }
+}
+
+/// The current rally, which starts with each serve.
+#[derive(Resource, Reflect, Clone, Default)]
+#[reflect(Resource)]
+pub struct Rally {
+    /// The number of paddle hits so far.
+    pub hits: u32,
+    /// The side that hit the ball most recently.
+    pub last_hit: Option<Side>,
+}
+
+impl Configure for Rally {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update((
+                reset_rally
+                    .run_if(on_event::<ServeLaunched>)
+                    .in_set(UpdateSystems::HandleEvents),
+                add_extra_ball
+                    .run_if(on_event::<PaddleHit>.and(MatchSetup::is_multi_ball))
+                    .in_set(UpdateSystems::HandleEvents),
+                regulate_ball_speed
+                    .in_set(UpdateSystems::Update)
+                    .run_if(Pause::is_disabled),
+            )),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_rally(mut rally: ResMut<Rally>) {
+    *rally = default();
+}
+
+/// Launch another ball from the center every few paddle hits in a rally.
+#[cfg_attr(feature = "native_dev", hot)]
+fn add_extra_ball(
+    mut commands: Commands,
+    mut paddle_events: EventReader<PaddleHit>,
+    config: ConfigRef<BallConfig>,
+    arena_config: ConfigRef<ArenaConfig>,
+    match_config: ConfigRef<MatchConfig>,
+    rally: Res<Rally>,
+    mut rng: ResMut<GameRng>,
+    ball_query: Query<(), With<Ball>>,
+) {
+    let side = rq!(paddle_events.read().last()).side;
+    let config = r!(config.get());
+    rq!(config.extra_ball_hits > 0 && rally.hits % config.extra_ball_hits == 0);
+    rq!(ball_query.iter().count() < config.max_balls);
+    let radius = r!(arena_config.get()).ball_radius;
+    let match_config = r!(match_config.get());
+
+    // Send the new ball away from the side that earned it.
+    let velocity = serve_direction(match_config, side.opponent(), &mut *rng) * config.base_speed;
+    commands
+        .spawn(ball_bundle(radius))
+        .insert(LinearVelocity(velocity));
+}
+
+/// Keep the ball moving at the rally speed, with enough horizontal motion to reach a paddle.
+#[cfg_attr(feature = "native_dev", hot)]
+fn regulate_ball_speed(
+    config: ConfigRef<BallConfig>,
+    power_up_config: ConfigRef<PowerUpConfig>,
+    setup: Res<MatchSetup>,
+    rally: Res<Rally>,
+    serve: Res<Serve>,
+    power_ups: Res<PowerUps>,
+    mut ball_query: Query<&mut LinearVelocity, With<Ball>>,
+) {
+    rq!(!serve.is_serving());
+    let config = r!(config.get());
+    let rally_speed = config.rally_speed(rally.hits);
+    // The top and bottom paddles need vertical motion to reach them in free-for-all.
+    let min_horizontal_ratio = if setup.is_free_for_all() {
+        0.0
+    } else {
+        config.min_horizontal_ratio
+    };
+
+    for mut velocity in &mut ball_query {
+        let speed = rally_speed
+            * power_up_config
+                .get()
+                .map_or(1.0, |x| power_ups.ball_speed_scale(velocity.0, x));
+        let regulated = regulate(velocity.0, speed, min_horizontal_ratio);
+        // Avoid triggering change detection every frame.
+        if velocity.0.distance_squared(regulated) > 1e-4 {
+            velocity.0 = regulated;
+        }
+    }
+}
+
+/// The velocity with the given speed, steered toward horizontal if needed.
+fn regulate(velocity: Vector, speed: f32, min_horizontal_ratio: f32) -> Vector {
+    let direction = velocity.normalize_or_zero();
+    if direction == Vector::ZERO {
+        return velocity;
+    }
+
+    let x = direction.x.abs().max(min_horizontal_ratio.clamp(0.0, 1.0));
+    let y = (1.0 - x * x).max(0.0).sqrt();
+    let x = if direction.x < 0.0 { -x } else { x };
+    let y = if direction.y < 0.0 { -y } else { y };
+
+    Vector::new(x, y) * speed
+}
+
+/// Send the ball off at an angle that depends on where it hit the paddle.
+fn deflect_off_paddle(
+    trigger: Trigger<OnCollisionStart>,
+    config: ConfigRef<BallConfig>,
+    mut rally: ResMut<Rally>,
+    mut ball_query: Query<(&Transform, &Collider, &mut LinearVelocity), With<Ball>>,
+    paddle_query: Query<(&Side, &Transform, &Collider), (With<Player>, Without<Ball>)>,
+) {
+    let (ball_transform, ball_collider, mut velocity) = rq!(ball_query.get_mut(trigger.target()));
+    let (&side, paddle_transform, paddle_collider) = rq!(paddle_query.get(trigger.collider));
+    let config = r!(config.get());
+
+    let ball_radius = ball_collider.shape().as_ball().map_or(0.0, |x| x.radius);
+    let paddle_half_length = paddle_collider
+        .shape()
+        .as_cuboid()
+        .map_or(0.0, |x| x.half_extents.max_element());
+
+    // Paddles on the top and bottom deflect the same way with the axes swapped.
+    let swap = |v: Vec2| if side.is_horizontal() { v.yx() } else { v };
+    velocity.0 = swap(deflect(
+        swap(ball_transform.translation.xy()),
+        swap(velocity.0),
+        swap(paddle_transform.translation.xy()),
+        paddle_half_length + ball_radius,
+        config.max_deflection_angle.to_radians(),
+    ));
+    rally.hits += 1;
+    rally.last_hit = Some(side);
+}
+
+/// The ball's velocity after hitting a paddle, keeping its speed.
+///
+/// Hitting the center of the paddle sends the ball straight back, and hitting
+/// the edge (`reach` away from the center) sends it at `max_angle`.
+fn deflect(
+    ball_pos: Vec2,
+    velocity: Vector,
+    paddle_pos: Vec2,
+    reach: f32,
+    max_angle: f32,
+) -> Vector {
+    let offset = if reach > 0.0 {
+        ((ball_pos.y - paddle_pos.y) / reach).clamp(-1.0, 1.0)
+    } else {
+        0.0
+    };
+    let angle = offset * max_angle;
+    let away = if ball_pos.x < paddle_pos.x { -1.0 } else { 1.0 };
+
+    Vector::new(away * angle.cos(), angle.sin()) * velocity.length()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    #[test]
+    fn test_deflect() {
+        let velocity = Vector::new(-300.0, 400.0);
+        let paddle = vec2(-400.0, 0.0);
+
+        // Center hit goes straight back.
+        let got = deflect(vec2(-390.0, 0.0), velocity, paddle, 100.0, FRAC_PI_4);
+        assert!((got - Vector::new(500.0, 0.0)).length() < 1e-3);
+
+        // Edge hits go out at the maximum angle.
+        let got = deflect(vec2(-390.0, -150.0), velocity, paddle, 100.0, FRAC_PI_4);
+        assert!((got.to_angle() + FRAC_PI_4).abs() < 1e-3);
+        assert!((got.length() - 500.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_regulate() {
+        // Speed is set without changing direction.
+        let got = regulate(Vector::new(-3.0, 4.0), 250.0, 0.5);
+        assert!((got - Vector::new(-150.0, 200.0)).length() < 1e-3);
+
+        // A nearly vertical ball is steered toward horizontal.
+        let got = regulate(Vector::new(1.0, -100.0), 100.0, 0.5);
+        assert!((got.x - 50.0).abs() < 1e-3);
+        assert!(got.y < 0.0);
+        assert!((got.length() - 100.0).abs() < 1e-3);
+    }
+}
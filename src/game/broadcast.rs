@@ -0,0 +1,332 @@
+use std::io;
+use std::io::Write as _;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+
+use crate::game::Ball;
+use crate::game::Player;
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::Slot;
+use crate::game::arena::ArenaConfig;
+use crate::game::event::GoalScored;
+use crate::game::event::MatchWon;
+use crate::game::event::PaddleHit;
+use crate::game::event::ServeLaunched;
+use crate::game::event::WallHit;
+use crate::game::layout::LayoutConfig;
+use crate::game::mode::MatchSetup;
+use crate::game::rules::Lives;
+use crate::game::rules::MatchClock;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(BroadcastScope, Broadcast)>();
+}
+
+/// The TCP port that matches are broadcast on.
+pub const BROADCAST_PORT: u16 = 7778;
+/// The most unsent bytes kept for one spectator before they're dropped for falling behind.
+const MAX_OUTGOING_BYTES: usize = 64 * 1024;
+
+/// Who can watch matches on this machine, from the `--broadcast-lan` command-line flag.
+#[derive(Resource, Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub enum BroadcastScope {
+    /// Only spectators on this machine.
+    #[default]
+    Local,
+    /// Spectators anywhere on the local network.
+    Lan,
+}
+
+impl Configure for BroadcastScope {
+    fn configure(app: &mut App) {
+        app.insert_resource(Self::from_args(std::env::args()));
+    }
+}
+
+impl BroadcastScope {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        if args.any(|x| x == "--broadcast-lan") {
+            Self::Lan
+        } else {
+            Self::Local
+        }
+    }
+
+    fn ip(self) -> IpAddr {
+        match self {
+            Self::Local => Ipv4Addr::LOCALHOST.into(),
+            Self::Lan => Ipv4Addr::UNSPECIFIED.into(),
+        }
+    }
+}
+
+/// Systems that start broadcasting a match.
+///
+/// Headless apps never run these, so tests don't open a socket for every match.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BroadcastSystems;
+
+/// A message to spectators, sent as one line of RON.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum BroadcastMessage {
+    /// Sent once to each new spectator.
+    Setup {
+        arena: ArenaConfig,
+        /// The index of the arena layout in [`LayoutConfig::layouts`].
+        layout: usize,
+        /// The display name for each side in the match.
+        names: Vec<(Side, String)>,
+    },
+    /// The state of the match on one frame.
+    Frame {
+        balls: Vec<Vec2>,
+        /// Every paddle, in player order.
+        paddles: Vec<BroadcastPaddle>,
+        /// The obstacle positions and angles in radians, in layout order.
+        obstacles: Vec<(Vec2, f32)>,
+        /// Each side's score, or its lives in free-for-all matches, in the same order as
+        /// the names.
+        scores: Vec<u32>,
+        events: Vec<BroadcastEvent>,
+    },
+}
+
+/// A paddle, as seen by spectators.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub struct BroadcastPaddle {
+    pub side: Side,
+    pub slot: Slot,
+    pub position: Vec2,
+    /// The current size, which can change with power-ups.
+    pub size: Vec2,
+}
+
+/// A gameplay event, as seen by spectators.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum BroadcastEvent {
+    Goal { scorer: Side },
+    PaddleHit { side: Side },
+    WallHit,
+    Serve,
+    MatchWon { winner: Side },
+}
+
+impl BroadcastMessage {
+    fn to_line(&self) -> Option<String> {
+        let mut line = ron::to_string(self).ok()?;
+        line.push('\n');
+        Some(line)
+    }
+}
+
+/// Sends the current match to any spectators connected on [`BROADCAST_PORT`], from as far
+/// away as the [`BroadcastScope`] allows.
+#[derive(Resource)]
+pub struct Broadcast {
+    listener: TcpListener,
+    spectators: Vec<Spectator>,
+}
+
+/// A connected spectator, and the bytes that haven't been sent to them yet.
+///
+/// The stream never blocks, so a slow spectator can't hold up the match. Whole messages
+/// are queued, and a partial write resumes where it left off on the next frame.
+struct Spectator {
+    stream: TcpStream,
+    outgoing: Vec<u8>,
+}
+
+impl Spectator {
+    /// Queue a line and write as much as the stream will take.
+    /// Returns false if the spectator disconnected or fell too far behind.
+    fn send(&mut self, line: &str) -> bool {
+        self.outgoing.extend_from_slice(line.as_bytes());
+        self.outgoing.len() <= MAX_OUTGOING_BYTES && self.flush()
+    }
+
+    fn flush(&mut self) -> bool {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return false,
+                Ok(len) => {
+                    self.outgoing.drain(..len);
+                },
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return true,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(_) => return false,
+            }
+        }
+        true
+    }
+}
+
+impl Configure for Broadcast {
+    fn configure(app: &mut App) {
+        app.add_systems(
+            StateFlush,
+            Screen::Gameplay.on_edge(stop_broadcast, start_broadcast.in_set(BroadcastSystems)),
+        );
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                (accept_spectators, broadcast_frame)
+                    .chain()
+                    .in_set(UpdateSystems::SyncLate)
+                    .run_if(resource_exists::<Self>),
+            ),
+        );
+    }
+}
+
+impl Broadcast {
+    fn new(scope: BroadcastScope, port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((scope.ip(), port))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            spectators: vec![],
+        })
+    }
+
+    /// Send a message to every spectator, dropping any that can't keep up.
+    fn send(&mut self, message: &BroadcastMessage) {
+        let line = r!(message.to_line());
+        self.spectators
+            .retain_mut(|spectator| spectator.send(&line));
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn start_broadcast(mut commands: Commands, scope: Res<BroadcastScope>) {
+    match Broadcast::new(*scope, BROADCAST_PORT) {
+        Ok(broadcast) => commands.insert_resource(broadcast),
+        // Another match on this machine may already be broadcasting.
+        Err(e) => info!("Not broadcasting this match: {e}"),
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn stop_broadcast(mut commands: Commands) {
+    commands.remove_resource::<Broadcast>();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn accept_spectators(
+    mut broadcast: ResMut<Broadcast>,
+    config: ConfigRef<ArenaConfig>,
+    setup: Res<MatchSetup>,
+) {
+    let config = r!(config.get());
+    while let Ok((stream, addr)) = broadcast.listener.accept() {
+        c!(stream.set_nonblocking(true));
+        c!(stream.set_nodelay(true));
+
+        let message = BroadcastMessage::Setup {
+            arena: config.clone(),
+            layout: setup.layout,
+            names: setup
+                .sides()
+                .iter()
+                .map(|&side| (side, setup.side_name(side)))
+                .collect(),
+        };
+        let line = c!(message.to_line());
+        let mut spectator = Spectator {
+            stream,
+            outgoing: vec![],
+        };
+        cq!(spectator.send(&line));
+        info!("Spectator connected from {addr}");
+        broadcast.spectators.push(spectator);
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn broadcast_frame(
+    mut broadcast: ResMut<Broadcast>,
+    layout_config: ConfigRef<LayoutConfig>,
+    setup: Res<MatchSetup>,
+    clock: Res<MatchClock>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    ball_query: Query<&Transform, With<Ball>>,
+    paddle_query: Query<(&Side, &Slot, &Transform, &Sprite), With<Player>>,
+    mut goal_events: EventReader<GoalScored>,
+    mut paddle_events: EventReader<PaddleHit>,
+    mut wall_events: EventReader<WallHit>,
+    mut serve_events: EventReader<ServeLaunched>,
+    mut match_events: EventReader<MatchWon>,
+) {
+    let mut events = vec![];
+    events.extend(
+        goal_events
+            .read()
+            .map(|x| BroadcastEvent::Goal { scorer: x.scorer }),
+    );
+    events.extend(
+        paddle_events
+            .read()
+            .map(|x| BroadcastEvent::PaddleHit { side: x.side }),
+    );
+    events.extend(wall_events.read().map(|_| BroadcastEvent::WallHit));
+    events.extend(serve_events.read().map(|_| BroadcastEvent::Serve));
+    events.extend(
+        match_events
+            .read()
+            .map(|x| BroadcastEvent::MatchWon { winner: x.winner }),
+    );
+    rq!(!broadcast.spectators.is_empty());
+
+    let paddles = setup
+        .paddles()
+        .iter()
+        .filter_map(|&(side, slot)| {
+            paddle_query
+                .iter()
+                .find(|(&x, &y, ..)| x == side && y == slot)
+                .map(|(_, _, transform, sprite)| BroadcastPaddle {
+                    side,
+                    slot,
+                    position: transform.translation.xy(),
+                    size: sprite.custom_size.unwrap_or_default(),
+                })
+        })
+        .collect();
+
+    // Obstacles follow the match clock, so their poses can be found in layout order.
+    let secs = clock.0.as_secs_f32();
+    let obstacles = layout_config
+        .get()
+        .and_then(|x| x.layouts.get(setup.layout))
+        .map(|layout| {
+            layout
+                .obstacles
+                .iter()
+                .map(|obstacle| {
+                    let (position, angle, ..) = obstacle.pose(secs);
+                    (position, angle)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    broadcast.send(&BroadcastMessage::Frame {
+        balls: ball_query
+            .iter()
+            .map(|transform| transform.translation.xy())
+            .collect(),
+        paddles,
+        obstacles,
+        scores: setup
+            .sides()
+            .iter()
+            .map(|&side| setup.side_value(&score, &lives, side))
+            .collect(),
+        events,
+    });
+}
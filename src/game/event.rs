@@ -0,0 +1,118 @@
+use crate::game::Ball;
+use crate::game::Boundary;
+use crate::game::Player;
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::ball::Rally;
+use crate::game::layout::Obstacle;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<GoalScored>();
+    app.add_event::<PaddleHit>();
+    app.add_event::<WallHit>();
+    app.add_event::<ServeLaunched>();
+    app.add_event::<MatchWon>();
+
+    app.add_observer(emit_ball_collision_events);
+}
+
+/// The ball went into a goal.
+#[derive(Event, Reflect, Copy, Clone, Debug)]
+pub struct GoalScored {
+    pub ball: Entity,
+    pub scorer: Side,
+    pub conceded: Side,
+}
+
+/// The ball hit a paddle.
+#[derive(Event, Reflect, Copy, Clone, Debug)]
+pub struct PaddleHit {
+    pub ball: Entity,
+    pub paddle: Entity,
+    pub side: Side,
+    pub position: Vec2,
+    pub speed: f32,
+}
+
+/// The ball bounced off a wall that isn't a goal, or an obstacle.
+#[derive(Event, Reflect, Copy, Clone, Debug)]
+pub struct WallHit {
+    pub ball: Entity,
+    /// The wall that was hit, or `None` for an [`Obstacle`].
+    pub boundary: Option<Boundary>,
+    pub position: Vec2,
+    pub speed: f32,
+}
+
+/// The ball was served.
+#[derive(Event, Reflect, Copy, Clone, Debug)]
+pub struct ServeLaunched {
+    pub ball: Entity,
+    pub toward: Side,
+    pub velocity: Vec2,
+}
+
+/// A side won the match.
+#[derive(Event, Reflect, Copy, Clone, Debug)]
+pub struct MatchWon {
+    pub winner: Side,
+    pub score: Score,
+}
+
+fn emit_ball_collision_events(
+    trigger: Trigger<OnCollisionStart>,
+    ball_query: Query<(&Transform, &LinearVelocity), With<Ball>>,
+    paddle_query: Query<&Side, With<Player>>,
+    boundary_query: Query<&Boundary>,
+    obstacle_query: Query<(), With<Obstacle>>,
+    rally: Res<Rally>,
+    mut goal_events: EventWriter<GoalScored>,
+    mut paddle_events: EventWriter<PaddleHit>,
+    mut wall_events: EventWriter<WallHit>,
+) {
+    let ball = trigger.target();
+    let (transform, velocity) = rq!(ball_query.get(ball));
+    let position = transform.translation.xy();
+    let speed = velocity.length();
+    let other = trigger.collider;
+
+    if let Ok(&side) = paddle_query.get(other) {
+        paddle_events.write(PaddleHit {
+            ball,
+            paddle: other,
+            side,
+            position,
+            speed,
+        });
+    } else if let Ok(&boundary) = boundary_query.get(other) {
+        // A wall is only a goal while a paddle defends it.
+        let conceded = boundary.goal_side();
+        if paddle_query.iter().any(|&side| side == conceded) {
+            // Credit the last side to hit the ball, unless it was an own goal.
+            let scorer = rally
+                .last_hit
+                .filter(|&side| side != conceded)
+                .unwrap_or(conceded.opponent());
+            goal_events.write(GoalScored {
+                ball,
+                scorer,
+                conceded,
+            });
+        } else {
+            wall_events.write(WallHit {
+                ball,
+                boundary: Some(boundary),
+                position,
+                speed,
+            });
+        }
+    } else if obstacle_query.contains(other) {
+        wall_events.write(WallHit {
+            ball,
+            boundary: None,
+            position,
+            speed,
+        });
+    }
+}
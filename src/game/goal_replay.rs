@@ -0,0 +1,262 @@
+use std::collections::VecDeque;
+
+use crate::game::Ball;
+use crate::game::Player;
+use crate::game::event::GoalScored;
+use crate::game::mode::MatchSetup;
+use crate::game::netplay::NetSession;
+use crate::game::rules::MatchConfig;
+use crate::game::rules::is_match_decided;
+use crate::game::score_goal;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+use crate::screen::gameplay::GameplayAction;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(GoalReplay, IsGoalReplayBanner)>();
+}
+
+/// The ball and paddles on one tick.
+#[derive(Clone, Default)]
+struct Snapshot(Vec<(Entity, Transform, LinearVelocity)>);
+
+impl Snapshot {
+    /// The snapshot between `self` and `other`, for smooth slow motion.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|&(entity, transform, velocity)| {
+                    let Some(&(_, next_transform, next_velocity)) =
+                        other.0.iter().find(|(x, ..)| *x == entity)
+                    else {
+                        return (entity, transform, velocity);
+                    };
+                    (
+                        entity,
+                        Transform {
+                            translation: transform.translation.lerp(next_transform.translation, t),
+                            ..transform
+                        },
+                        LinearVelocity(velocity.0.lerp(next_velocity.0, t)),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Replays the last few seconds before each goal in slow motion, before the next serve.
+///
+/// The game is paused during the replay, and unpausing ends it. Any player can skip it with
+/// their skip control. LAN matches can't pause, so they skip goal replays. So do multi-ball
+/// matches, since balls that score are removed, and the goal that wins the match.
+#[derive(Resource, Default)]
+pub struct GoalReplay {
+    /// The most recent ticks, oldest first.
+    history: VecDeque<Snapshot>,
+    playback: Option<GoalReplayPlayback>,
+}
+
+struct GoalReplayPlayback {
+    frames: Vec<Snapshot>,
+    /// The time into the replay in seconds, at normal speed.
+    elapsed: f32,
+    /// The ball and paddles when the replay started, to restore afterwards.
+    live: Snapshot,
+}
+
+impl Configure for GoalReplay {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            (
+                Screen::Gameplay.on_enter(reset_goal_replay),
+                Pause.on_exit(end_goal_replay),
+            ),
+        );
+        app.add_systems(
+            FixedUpdate,
+            Screen::Gameplay.on_update((
+                record_goal_replay_tick
+                    .in_set(UpdateSystems::SyncEarly)
+                    .run_if(Pause::is_disabled),
+                begin_goal_replay
+                    .in_set(UpdateSystems::HandleEvents)
+                    .after(score_goal)
+                    .run_if(
+                        Pause::is_disabled
+                            .and(not(resource_exists::<NetSession>))
+                            .and(not(MatchSetup::is_multi_ball))
+                            .and(not(is_match_decided)),
+                    ),
+            )),
+        );
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                skip_goal_replay_on_input
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(GoalReplay::is_playing),
+                play_goal_replay
+                    .in_set(UpdateSystems::Update)
+                    .run_if(GoalReplay::is_playing),
+            )),
+        );
+    }
+}
+
+impl GoalReplay {
+    pub fn is_playing(goal_replay: Res<Self>) -> bool {
+        goal_replay.playback.is_some()
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_goal_replay(mut goal_replay: ResMut<GoalReplay>) {
+    *goal_replay = default();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_goal_replay_tick(
+    time: Res<Time>,
+    config: ConfigRef<MatchConfig>,
+    mut goal_replay: ResMut<GoalReplay>,
+    snapshot_query: Query<(Entity, &Transform, &LinearVelocity), Or<(With<Ball>, With<Player>)>>,
+) {
+    let config = r!(config.get());
+    let capacity = (config.goal_replay_secs / time.delta_secs()) as usize;
+    rq!(capacity > 0);
+
+    let history = &mut goal_replay.history;
+    while history.len() >= capacity {
+        history.pop_front();
+    }
+    history.push_back(Snapshot(
+        snapshot_query
+            .iter()
+            .map(|(entity, &transform, &velocity)| (entity, transform, velocity))
+            .collect(),
+    ));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn begin_goal_replay(
+    mut commands: Commands,
+    mut goal_events: EventReader<GoalScored>,
+    screen_root: Res<ScreenRoot>,
+    mut goal_replay: ResMut<GoalReplay>,
+    mut pause: NextMut<Pause>,
+    snapshot_query: Query<(Entity, &Transform, &LinearVelocity), Or<(With<Ball>, With<Player>)>>,
+) {
+    rq!(goal_events.read().count() > 0);
+    rq!(goal_replay.history.len() > 1);
+
+    goal_replay.playback = Some(GoalReplayPlayback {
+        frames: goal_replay.history.drain(..).collect(),
+        elapsed: 0.0,
+        live: Snapshot(
+            snapshot_query
+                .iter()
+                .map(|(entity, &transform, &velocity)| (entity, transform, velocity))
+                .collect(),
+        ),
+    });
+    pause.enable_default();
+    commands
+        .entity(screen_root.ui)
+        .with_child(goal_replay_banner());
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn play_goal_replay(
+    time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
+    config: ConfigRef<MatchConfig>,
+    mut goal_replay: ResMut<GoalReplay>,
+    mut pause: NextMut<Pause>,
+    mut snapshot_query: Query<(&mut Transform, &mut LinearVelocity)>,
+) {
+    let config = r!(config.get());
+    let playback = r!(goal_replay.playback.as_mut());
+    playback.elapsed += time.delta_secs() * config.goal_replay_speed;
+
+    let t = playback.elapsed / fixed_time.timestep().as_secs_f32();
+    let i = t as usize;
+    if i + 1 >= playback.frames.len() {
+        pause.disable();
+        return;
+    }
+
+    let snapshot = playback.frames[i].lerp(&playback.frames[i + 1], t.fract());
+    for (entity, transform, velocity) in snapshot.0 {
+        let (mut old_transform, mut old_velocity) = cq!(snapshot_query.get_mut(entity));
+        *old_transform = transform;
+        *old_velocity = velocity;
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn end_goal_replay(
+    mut commands: Commands,
+    mut goal_replay: ResMut<GoalReplay>,
+    mut snapshot_query: Query<(&mut Transform, &mut LinearVelocity)>,
+    banner_query: Query<Entity, With<IsGoalReplayBanner>>,
+) {
+    let playback = rq!(goal_replay.playback.take());
+    for (entity, transform, velocity) in playback.live.0 {
+        let (mut old_transform, mut old_velocity) = cq!(snapshot_query.get_mut(entity));
+        *old_transform = transform;
+        *old_velocity = velocity;
+    }
+    for entity in &banner_query {
+        commands.entity(entity).try_despawn();
+    }
+}
+
+/// The "REPLAY" banner shown during a goal replay.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsGoalReplayBanner;
+
+impl Configure for IsGoalReplayBanner {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+fn goal_replay_banner() -> impl Bundle {
+    (
+        Name::new("GoalReplayBanner"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceBetween,
+            // Leave room for the HUD at the top.
+            padding: UiRect::new(Vw(2.0), Vw(2.0), Vw(11.0), Vw(2.0)),
+            ..Node::DEFAULT.full_size().abs()
+        },
+        Pickable::IGNORE,
+        IsGoalReplayBanner,
+        children![
+            widget::big_label("[b]REPLAY"),
+            widget::button("Skip", skip_goal_replay),
+        ],
+    )
+}
+
+fn skip_goal_replay(_: Trigger<Pointer<Click>>, mut pause: NextMut<Pause>) {
+    pause.disable();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn skip_goal_replay_on_input(
+    action_state: Res<ActionState<GameplayAction>>,
+    mut pause: NextMut<Pause>,
+) {
+    // The gameplay controls read from every keyboard and gamepad, so any player can skip.
+    rq!(action_state.just_pressed(&GameplayAction::SkipReplay));
+    pause.disable();
+}
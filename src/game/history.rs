@@ -0,0 +1,298 @@
+use bevy_simple_prefs::Prefs;
+use bevy_simple_prefs::PrefsPlugin;
+
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::mode::MatchSetup;
+use crate::game::mode::PlayMode;
+use crate::game::rules::Lives;
+use crate::game::rules::MatchResult;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(MatchHistory, HistoryFile)>();
+}
+
+/// A finished match.
+#[derive(Reflect, Clone, Default, Debug)]
+pub struct MatchRecord {
+    /// When the match finished, in seconds since the Unix epoch, if known.
+    pub date: Option<u64>,
+    pub mode: PlayMode,
+    /// The display names for the left and right sides.
+    pub players: [String; 2],
+    pub score: Score,
+    pub duration: Duration,
+    /// How each side finished a free-for-all match, in [`Side::ALL`] order.
+    #[reflect(default)]
+    pub placements: Option<Vec<Placement>>,
+}
+
+impl MatchRecord {
+    pub fn winner(&self) -> Option<Side> {
+        match &self.placements {
+            Some(placements) => placements.iter().find(|x| x.place == 1).map(|x| x.side),
+            None => self.score.leader(),
+        }
+    }
+
+    /// The finish date as `YYYY-MM-DD`, in UTC.
+    pub fn date_string(&self) -> String {
+        let Some(secs) = self.date else {
+            return "-".to_string();
+        };
+
+        // Convert days since the epoch to a civil date.
+        // See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+        let z = (secs / 86400) as i64 + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + (month <= 2) as i64;
+        format!("{year}-{month:02}-{day:02}")
+    }
+}
+
+/// How one side finished a free-for-all match.
+#[derive(Reflect, Clone, Debug)]
+pub struct Placement {
+    pub side: Side,
+    pub name: String,
+    /// The finishing place, starting from 1 for the winner.
+    /// Sides with the same lives left share a place.
+    pub place: u32,
+    /// The lives left at the end of the match.
+    pub lives: u32,
+}
+
+impl Placement {
+    /// Every side's placement at the end of a free-for-all match.
+    fn all(setup: &MatchSetup, lives: &Lives, winner: Side) -> Vec<Self> {
+        let sides = setup.sides();
+        sides
+            .iter()
+            .map(|&side| {
+                // The winner can be tied on lives at the time limit, but still places first.
+                let ahead = sides
+                    .iter()
+                    .filter(|&&x| x != side && (x == winner || lives.get(x) > lives.get(side)))
+                    .count();
+                Self {
+                    side,
+                    name: setup.side_name(side),
+                    place: if side == winner { 1 } else { ahead as u32 + 1 },
+                    lives: lives.get(side),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The order to list past matches in.
+#[derive(Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub enum HistorySort {
+    #[default]
+    Newest,
+    BiggestWin,
+    HighestScore,
+    Longest,
+}
+
+impl HistorySort {
+    pub const ALL: [Self; 4] = [
+        Self::Newest,
+        Self::BiggestWin,
+        Self::HighestScore,
+        Self::Longest,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Newest => "Newest",
+            Self::BiggestWin => "Biggest win",
+            Self::HighestScore => "Highest score",
+            Self::Longest => "Longest",
+        }
+    }
+}
+
+/// Every finished match, oldest first, saved to `history.ron`.
+#[derive(Resource, Reflect, Clone, Default, Debug)]
+#[reflect(Resource)]
+pub struct MatchHistory(pub Vec<MatchRecord>);
+
+impl Configure for MatchHistory {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(StateFlush, Screen::Results.on_enter(record_match));
+    }
+}
+
+impl MatchHistory {
+    /// The oldest matches are forgotten after this many.
+    const MAX_LEN: usize = 500;
+
+    /// The matches played in the given mode, or in any mode, in the given order.
+    pub fn list(&self, mode: Option<PlayMode>, sort: HistorySort) -> Vec<&MatchRecord> {
+        let mut records = self
+            .0
+            .iter()
+            .rev()
+            .filter(|x| mode.is_none_or(|mode| x.mode == mode))
+            .collect::<Vec<_>>();
+        // Stable sorts keep the newest first among equal records.
+        match sort {
+            HistorySort::Newest => {},
+            HistorySort::BiggestWin => records.sort_by_key(|x| std::cmp::Reverse(x.score.lead())),
+            HistorySort::HighestScore => records
+                .sort_by_key(|x| std::cmp::Reverse(x.winner().map_or(0, |side| x.score.get(side)))),
+            HistorySort::Longest => records.sort_by_key(|x| std::cmp::Reverse(x.duration)),
+        }
+        records
+    }
+
+    fn push(&mut self, record: MatchRecord) {
+        self.0.push(record);
+        let excess = self.0.len().saturating_sub(Self::MAX_LEN);
+        self.0.drain(..excess);
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_match(
+    result: Res<MatchResult>,
+    setup: Res<MatchSetup>,
+    lives: Res<Lives>,
+    mut history: ResMut<MatchHistory>,
+) {
+    history.push(MatchRecord {
+        date: unix_time(),
+        mode: setup.mode,
+        players: [Side::Left, Side::Right].map(|side| setup.side_name(side)),
+        score: result.score,
+        duration: result.duration,
+        placements: setup
+            .is_free_for_all()
+            .then(|| Placement::all(&setup, &lives, result.winner)),
+    });
+}
+
+/// The current time in seconds since the Unix epoch, if the platform has a clock.
+fn unix_time() -> Option<u64> {
+    #[cfg(feature = "native")]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|x| x.as_secs());
+    #[cfg(not(feature = "native"))]
+    None
+}
+
+#[derive(Prefs, Reflect, Default)]
+struct HistoryFile {
+    pub match_history: MatchHistory,
+}
+
+impl Configure for HistoryFile {
+    fn configure(app: &mut App) {
+        #[cfg(feature = "native")]
+        let path = r!(crate::util::config_local_dir());
+
+        app.add_plugins(PrefsPlugin::<HistoryFile> {
+            filename: "history.ron".to_string(),
+            #[cfg(feature = "native")]
+            path,
+            ..default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(mode: PlayMode, player1: u32, player2: u32, secs: u64) -> MatchRecord {
+        MatchRecord {
+            mode,
+            score: Score { player1, player2 },
+            duration: Duration::from_secs(secs),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_date_string() {
+        let date = |secs| {
+            MatchRecord {
+                date: Some(secs),
+                ..default()
+            }
+            .date_string()
+        };
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(951_782_400), "2000-02-29");
+        assert_eq!(date(1_792_195_200), "2026-10-17");
+        assert_eq!(MatchRecord::default().date_string(), "-");
+    }
+
+    #[test]
+    fn test_free_for_all_placements() {
+        let setup = MatchSetup {
+            mode: PlayMode::FreeForAll,
+            ..default()
+        };
+        let placements = Placement::all(&setup, &Lives([0, 2, 1, 0]), Side::Right);
+        let places = placements
+            .iter()
+            .map(|x| (x.side, x.place, x.lives))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            places,
+            [
+                (Side::Left, 3, 0),
+                (Side::Right, 1, 2),
+                (Side::Top, 2, 1),
+                (Side::Bottom, 3, 0),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_list() {
+        let history = MatchHistory(vec![
+            record(PlayMode::VersusCpu, 11, 9, 200),
+            record(PlayMode::LocalVersus, 3, 11, 100),
+            record(PlayMode::VersusCpu, 11, 2, 300),
+        ]);
+        let scores = |mode, sort| {
+            history
+                .list(mode, sort)
+                .iter()
+                .map(|x| (x.score.player1, x.score.player2))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            scores(None, HistorySort::Newest),
+            [(11, 2), (3, 11), (11, 9)],
+        );
+        assert_eq!(
+            scores(Some(PlayMode::VersusCpu), HistorySort::Newest),
+            [(11, 2), (11, 9)],
+        );
+        assert_eq!(
+            scores(None, HistorySort::BiggestWin),
+            [(11, 2), (3, 11), (11, 9)],
+        );
+        assert_eq!(
+            scores(Some(PlayMode::VersusCpu), HistorySort::Longest),
+            [(11, 2), (11, 9)],
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use crate::animation::offset::NodeOffset;
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::mode::MatchSetup;
+use crate::game::powerup::PowerUps;
+use crate::game::rules::Lives;
+use crate::game::rules::MatchClock;
+use crate::game::rules::MatchConfig;
+use crate::game::serve::Serve;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        state!(Screen::Gameplay | Screen::Replay).on_enter(spawn_hud),
+    );
+
+    app.configure::<(HudScore, HudName, HudServe, HudPowerUps, IsHudTimer)>();
+}
+
+/// How long the score "pop" lasts after a point or a lost life, in seconds.
+const POP_SECS: f32 = 0.35;
+
+/// How high the score jumps during the "pop", in `Vw`.
+const POP_HEIGHT: f32 = 1.5;
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_hud(
+    mut commands: Commands,
+    screen_root: Res<ScreenRoot>,
+    setup: Res<MatchSetup>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+) {
+    // Each side's panel sits on the same side of the timer as its paddle.
+    let (left, right, width): (&[Side], &[Side], _) = if setup.is_free_for_all() {
+        (&[Side::Left, Side::Top], &[Side::Bottom, Side::Right], 18.0)
+    } else {
+        (&[Side::Left], &[Side::Right], 25.0)
+    };
+    let panels = |sides: &[Side]| {
+        sides
+            .iter()
+            .map(|&side| hud_side(side, setup.side_value(&score, &lives, side), width))
+            .collect::<Vec<_>>()
+    };
+
+    commands.entity(screen_root.ui).with_child((
+        Name::new("Hud"),
+        Node {
+            align_items: AlignItems::Start,
+            justify_content: JustifyContent::SpaceBetween,
+            padding: UiRect::all(Vw(1.5)),
+            ..Node::DEFAULT.full_size().abs()
+        },
+        Pickable::IGNORE,
+        Children::spawn((
+            SpawnIter(panels(left).into_iter()),
+            Spawn((widget::label(""), IsHudTimer)),
+            SpawnIter(panels(right).into_iter()),
+        )),
+    ));
+}
+
+/// The name, score or lives, serve indicator, and power-ups for one side.
+fn hud_side(side: Side, value: u32, width: f32) -> impl Bundle {
+    (
+        Name::new(format!("Hud{side:?}")),
+        Node {
+            width: Vw(width),
+            row_gap: Vw(0.5),
+            ..Node::COLUMN_CENTER
+        },
+        children![
+            (
+                Name::new("NameRow"),
+                Node {
+                    column_gap: Vw(1.5),
+                    ..Node::ROW_CENTER
+                },
+                children![
+                    (widget::label(""), HudName(side)),
+                    (widget::label(""), HudServe(side)),
+                ],
+            ),
+            (
+                widget::big_label(format!("[b]{value}")),
+                HudScore {
+                    side,
+                    shown: value,
+                    pop: 0.0,
+                },
+                NodeOffset::default(),
+            ),
+            (widget::label(""), HudPowerUps(side)),
+        ],
+    )
+}
+
+/// The label that shows one side's score, or its lives in free-for-all matches.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HudScore {
+    side: Side,
+    /// The score or lives currently shown.
+    shown: u32,
+    /// The time left in the "pop" animation, from 1 to 0.
+    pop: f32,
+}
+
+impl Configure for HudScore {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_update(update_hud_scores.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_hud_scores(
+    time: Res<Time>,
+    setup: Res<MatchSetup>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    mut label_query: Query<(&mut HudScore, &mut RichText, &mut NodeOffset)>,
+) {
+    for (mut hud_score, mut label, mut offset) in &mut label_query {
+        let value = setup.side_value(&score, &lives, hud_score.side);
+        if value != hud_score.shown {
+            // Only pop when a point is scored or a life is lost, not when they're reset.
+            let is_goal = if setup.is_free_for_all() {
+                value < hud_score.shown
+            } else {
+                value > hud_score.shown
+            };
+            if is_goal {
+                hud_score.pop = 1.0;
+            }
+            hud_score.shown = value;
+            label.sections = parse_rich(format!("[b]{value}"));
+        }
+
+        if hud_score.pop > 0.0 {
+            hud_score.pop = (hud_score.pop - time.delta_secs() / POP_SECS).max(0.0);
+            let height = (hud_score.pop * std::f32::consts::PI).sin();
+            offset.y = Vw(-POP_HEIGHT * height);
+        }
+    }
+}
+
+/// The label that shows the player name for one side.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HudName(Side);
+
+impl Configure for HudName {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_update(update_hud_names.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_hud_names(
+    setup: Res<MatchSetup>,
+    mut label_query: Query<(&HudName, &mut RichText), Added<HudName>>,
+) {
+    for (name, mut label) in &mut label_query {
+        label.sections = parse_rich(setup.side_name(name.0));
+    }
+}
+
+/// The label that shows when the ball is about to be served toward one side.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HudServe(Side);
+
+impl Configure for HudServe {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_update(update_hud_serve.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_hud_serve(serve: Res<Serve>, mut label_query: Query<(&HudServe, &mut RichText)>) {
+    let receiver = serve.toward.filter(|_| serve.is_serving());
+    for (hud_serve, mut label) in &mut label_query {
+        let text = if receiver == Some(hud_serve.0) {
+            "[b]Serve"
+        } else {
+            ""
+        };
+        label.sections = parse_rich(text);
+    }
+}
+
+/// The label that shows the power-ups one side has collected, with the time left on each.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HudPowerUps(Side);
+
+impl Configure for HudPowerUps {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_update(update_hud_power_ups.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_hud_power_ups(
+    power_ups: Res<PowerUps>,
+    mut label_query: Query<(&HudPowerUps, &mut RichText)>,
+) {
+    rq!(power_ups.is_changed());
+    for (hud_power_ups, mut label) in &mut label_query {
+        let text = power_ups
+            .active
+            .iter()
+            .filter(|x| x.side == hud_power_ups.0)
+            .map(|x| format!("{} {}", x.kind.name(), x.timer.remaining_secs().ceil()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        label.sections = parse_rich(text);
+    }
+}
+
+/// The label that shows the match time, or the time left if there's a time limit.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsHudTimer;
+
+impl Configure for IsHudTimer {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_update(update_hud_timer.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_hud_timer(
+    config: ConfigRef<MatchConfig>,
+    clock: Res<MatchClock>,
+    mut label_query: Query<&mut RichText, With<IsHudTimer>>,
+) {
+    let config = r!(config.get());
+    let elapsed = clock.0.as_secs_f32();
+    let secs = config.time_remaining(elapsed).map_or(elapsed, f32::ceil) as u32;
+    let text = format!("{}:{:02}", secs / 60, secs % 60);
+    for mut label in &mut label_query {
+        label.sections = parse_rich(&text);
+    }
+}
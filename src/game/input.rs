@@ -0,0 +1,172 @@
+use crate::core::input::ControlSlot;
+use crate::core::input::Controls;
+use crate::game::Player;
+use crate::game::Side;
+use crate::game::Slot;
+use crate::game::ai::CpuController;
+use crate::game::arena::ArenaConfig;
+use crate::game::mode::MatchSetup;
+use crate::game::powerup::PowerUps;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(PaddleAction, PaddleInput)>();
+}
+
+/// Moving a paddle. On the top and bottom, "up" moves right and "down" moves left.
+///
+/// Players 3 and 4 move up with their "right" control and down with their "left" control.
+#[derive(Actionlike, Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PaddleAction {
+    Up,
+    Down,
+    #[actionlike(Axis)]
+    Move,
+}
+
+impl Configure for PaddleAction {
+    fn configure(app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<Self>::default());
+        app.add_systems(
+            Update,
+            apply_paddle_controls
+                .run_if(resource_changed::<Controls>)
+                .in_set(UpdateSystems::Update),
+        );
+        app.add_systems(
+            FixedUpdate,
+            Screen::Gameplay.on_update(
+                record_paddle_input
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+    }
+}
+
+impl PaddleAction {
+    /// The input map for one player's paddle on the given side.
+    ///
+    /// Gamepad bindings are only added for a player with their own gamepad, since an input
+    /// map without one reads from every gamepad.
+    pub fn input_map(
+        controls: &Controls,
+        player: usize,
+        side: Side,
+        gamepad: Option<Entity>,
+    ) -> InputMap<Self> {
+        let (up, down) = match player {
+            0 => (ControlSlot::Player1Up, ControlSlot::Player1Down),
+            1 => (ControlSlot::Player2Up, ControlSlot::Player2Down),
+            2 => (ControlSlot::Player3Right, ControlSlot::Player3Left),
+            _ => (ControlSlot::Player4Right, ControlSlot::Player4Left),
+        };
+        let axis = if side.is_horizontal() {
+            GamepadControlAxis::LEFT_X
+        } else {
+            GamepadControlAxis::LEFT_Y
+        };
+
+        let mut input_map = InputMap::default();
+        if let Some(gamepad) = gamepad {
+            input_map.insert_axis(Self::Move, axis.with_deadzone_symmetric(0.1));
+            controls.get(up).insert_into(&mut input_map, Self::Up);
+            controls.get(down).insert_into(&mut input_map, Self::Down);
+            input_map.set_gamepad(gamepad);
+        } else {
+            controls.get(up).insert_keys_into(&mut input_map, Self::Up);
+            controls
+                .get(down)
+                .insert_keys_into(&mut input_map, Self::Down);
+        }
+
+        input_map
+    }
+
+    /// The movement direction from -1 (down) to 1 (up).
+    fn direction(action_state: &ActionState<Self>) -> f32 {
+        let up = action_state.pressed(&Self::Up) as i8;
+        let down = action_state.pressed(&Self::Down) as i8;
+        ((up - down) as f32 + action_state.clamped_value(&Self::Move)).clamp(-1.0, 1.0)
+    }
+}
+
+/// The direction a human-controlled paddle is moving this tick.
+///
+/// This is quantized so that a recorded replay moves the paddle exactly the same way.
+#[derive(Component, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct PaddleInput(pub i8);
+
+impl Configure for PaddleInput {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update(
+                move_paddles
+                    .in_set(UpdateSystems::RecordInput)
+                    .after(record_paddle_input),
+            ),
+        );
+    }
+}
+
+impl PaddleInput {
+    pub fn from_direction(direction: f32) -> Self {
+        Self((direction.clamp(-1.0, 1.0) * i8::MAX as f32).round() as i8)
+    }
+
+    pub fn direction(self) -> f32 {
+        (self.0 as f32 / i8::MAX as f32).clamp(-1.0, 1.0)
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+pub(super) fn record_paddle_input(
+    mut paddle_query: Query<(&ActionState<PaddleAction>, &mut PaddleInput), With<Player>>,
+) {
+    for (action_state, mut input) in &mut paddle_query {
+        input.set_if_neq(PaddleInput::from_direction(PaddleAction::direction(
+            action_state,
+        )));
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+pub(super) fn move_paddles(
+    time: Res<Time>,
+    config: ConfigRef<ArenaConfig>,
+    power_ups: Res<PowerUps>,
+    mut paddle_query: Query<
+        (&Side, &PaddleInput, &mut LinearVelocity),
+        (With<Player>, Without<CpuController>),
+    >,
+) {
+    let speed = time.delta_secs() * r!(config.get()).paddle_speed_factor;
+    for (&side, input, mut velocity) in &mut paddle_query {
+        let direction = if power_ups.is_reversed(side) {
+            -input.direction()
+        } else {
+            input.direction()
+        };
+        if side.is_horizontal() {
+            velocity.x = direction * speed;
+        } else {
+            velocity.y = direction * speed;
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_paddle_controls(
+    controls: Res<Controls>,
+    setup: Res<MatchSetup>,
+    mut paddle_query: Query<(&Side, &Slot, &mut InputMap<PaddleAction>), With<Player>>,
+) {
+    for (&side, &slot, mut input_map) in &mut paddle_query {
+        let player = cq!(setup.player_index(side, slot));
+        *input_map = PaddleAction::input_map(&controls, player, side, input_map.gamepad());
+    }
+}
@@ -0,0 +1,276 @@
+use std::f32::consts::TAU;
+
+use crate::game::mode::MatchSetup;
+use crate::game::rules::MatchClock;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<LayoutConfig>, Obstacle)>();
+}
+
+/// The arena layouts that can be chosen before a match.
+#[derive(Asset, Reflect, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct LayoutConfig {
+    /// The layouts in menu order. The first is used by default, and in LAN matches.
+    pub layouts: Vec<ArenaLayout>,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            layouts: vec![
+                ArenaLayout {
+                    name: "Classic".to_string(),
+                    obstacles: vec![],
+                },
+                ArenaLayout {
+                    name: "Pillars".to_string(),
+                    obstacles: vec![
+                        Obstacle::rectangle(vec2(-250.0, 150.0), vec2(30.0, 60.0)),
+                        Obstacle::rectangle(vec2(250.0, 150.0), vec2(30.0, 60.0)),
+                        Obstacle::rectangle(vec2(-250.0, -150.0), vec2(30.0, 60.0)),
+                        Obstacle::rectangle(vec2(250.0, -150.0), vec2(30.0, 60.0)),
+                        Obstacle::circle(vec2(0.0, 160.0), 25.0),
+                        Obstacle::circle(vec2(0.0, -160.0), 25.0),
+                    ],
+                },
+                ArenaLayout {
+                    name: "Moving gate".to_string(),
+                    obstacles: vec![
+                        Obstacle::rectangle(vec2(0.0, 180.0), vec2(20.0, 120.0)).with_motion(
+                            ObstacleMotion::Slide {
+                                offset: vec2(0.0, 60.0),
+                                period: 4.0,
+                            },
+                        ),
+                        Obstacle::rectangle(vec2(0.0, -180.0), vec2(20.0, 120.0)).with_motion(
+                            ObstacleMotion::Slide {
+                                offset: vec2(0.0, -60.0),
+                                period: 4.0,
+                            },
+                        ),
+                    ],
+                },
+                ArenaLayout {
+                    name: "Spinners".to_string(),
+                    obstacles: vec![
+                        Obstacle::rectangle(vec2(-250.0, 0.0), vec2(140.0, 14.0))
+                            .with_motion(ObstacleMotion::Rotate { speed: 90.0 }),
+                        Obstacle::rectangle(vec2(250.0, 0.0), vec2(140.0, 14.0))
+                            .with_motion(ObstacleMotion::Rotate { speed: -90.0 }),
+                    ],
+                },
+            ],
+        }
+    }
+}
+
+impl Config for LayoutConfig {
+    const FILE: &'static str = "layout.ron";
+}
+
+impl LayoutConfig {
+    /// The index of the layout `step` places after `index` in menu order, wrapping around.
+    pub fn cycle(&self, index: usize, step: isize) -> usize {
+        if self.layouts.is_empty() {
+            return 0;
+        }
+        (index as isize + step).rem_euclid(self.layouts.len() as isize) as usize
+    }
+}
+
+/// A named set of obstacles to place in the arena.
+#[derive(Reflect, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArenaLayout {
+    /// The name shown in the menu.
+    pub name: String,
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+}
+
+/// Something in the arena for the ball to bounce off, besides the walls and paddles.
+#[derive(Component, Reflect, Clone, Debug, Serialize, Deserialize)]
+#[reflect(Component)]
+#[serde(deny_unknown_fields)]
+pub struct Obstacle {
+    pub shape: ObstacleShape,
+    /// The position of the center in world units, relative to the center of the arena.
+    pub position: Vec2,
+    #[serde(default)]
+    pub motion: ObstacleMotion,
+}
+
+impl Configure for Obstacle {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update(
+                move_obstacles
+                    .in_set(UpdateSystems::Update)
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+    }
+}
+
+impl Obstacle {
+    fn rectangle(position: Vec2, size: Vec2) -> Self {
+        Self {
+            shape: ObstacleShape::Rectangle(size),
+            position,
+            motion: default(),
+        }
+    }
+
+    fn circle(position: Vec2, radius: f32) -> Self {
+        Self {
+            shape: ObstacleShape::Circle(radius),
+            position,
+            motion: default(),
+        }
+    }
+
+    fn with_motion(mut self, motion: ObstacleMotion) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    /// The position, angle, linear velocity, and angular velocity after `secs` of play.
+    pub fn pose(&self, secs: f32) -> (Vec2, f32, Vec2, f32) {
+        match self.motion {
+            ObstacleMotion::Static => (self.position, 0.0, Vec2::ZERO, 0.0),
+            ObstacleMotion::Slide { offset, period } => {
+                let rate = TAU / period.max(0.1);
+                let phase = secs * rate;
+                (
+                    self.position + offset * phase.sin(),
+                    0.0,
+                    offset * rate * phase.cos(),
+                    0.0,
+                )
+            },
+            ObstacleMotion::Rotate { speed } => {
+                let speed = speed.to_radians();
+                (self.position, speed * secs, Vec2::ZERO, speed)
+            },
+        }
+    }
+}
+
+#[derive(Reflect, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum ObstacleShape {
+    /// A round bumper with the given radius in world units.
+    Circle(f32),
+    /// A block or bar with the given width and height in world units.
+    Rectangle(Vec2),
+}
+
+impl ObstacleShape {
+    /// The width and height of the bounding box in world units.
+    pub fn size(self) -> Vec2 {
+        match self {
+            Self::Circle(radius) => Vec2::splat(radius * 2.0),
+            Self::Rectangle(size) => size,
+        }
+    }
+}
+
+/// How an obstacle moves during a match. Moving obstacles follow the [`MatchClock`],
+/// so they're in the same place in replays and on both machines in LAN matches.
+#[derive(Reflect, Copy, Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ObstacleMotion {
+    #[default]
+    Static,
+    /// Slide back and forth between `position - offset` and `position + offset`,
+    /// taking `period` seconds for each round trip.
+    Slide { offset: Vec2, period: f32 },
+    /// Spin around the center at `speed` degrees per second, counterclockwise.
+    Rotate { speed: f32 },
+}
+
+pub(super) fn add_obstacles(
+    mut commands: Commands,
+    config: ConfigRef<LayoutConfig>,
+    setup: Res<MatchSetup>,
+) {
+    let config = r!(config.get());
+    let layout = rq!(config.layouts.get(setup.layout));
+
+    for (i, obstacle) in layout.obstacles.iter().enumerate() {
+        let collider = match obstacle.shape {
+            ObstacleShape::Circle(radius) => Collider::circle(radius),
+            ObstacleShape::Rectangle(size) => Collider::rectangle(size.x, size.y),
+        };
+        let body = if obstacle.motion == ObstacleMotion::Static {
+            RigidBody::Static
+        } else {
+            RigidBody::Kinematic
+        };
+        commands.spawn((
+            Name::new(format!("Obstacle{i}")),
+            obstacle.clone(),
+            body,
+            collider,
+            Transform::from_translation(obstacle.position.extend(0.)),
+            Sprite::from_color(
+                Srgba::from_vec3(Vec3::new(0.5, 0.25, 0.25)),
+                obstacle.shape.size(),
+            ),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn move_obstacles(
+    clock: Res<MatchClock>,
+    mut obstacle_query: Query<(
+        &Obstacle,
+        &mut Position,
+        &mut Rotation,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+) {
+    let secs = clock.0.as_secs_f32();
+    for (obstacle, mut position, mut rotation, mut velocity, mut angular_velocity) in
+        &mut obstacle_query
+    {
+        cq!(obstacle.motion != ObstacleMotion::Static);
+        let (new_position, angle, new_velocity, new_angular_velocity) = obstacle.pose(secs);
+        position.0 = new_position;
+        *rotation = Rotation::radians(angle);
+        velocity.0 = new_velocity;
+        angular_velocity.0 = new_angular_velocity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slide_pose() {
+        let obstacle = Obstacle::rectangle(vec2(0.0, 100.0), vec2(20.0, 50.0)).with_motion(
+            ObstacleMotion::Slide {
+                offset: vec2(0.0, 40.0),
+                period: 4.0,
+            },
+        );
+
+        // A quarter of the way through, it's furthest along the offset and at rest.
+        let (position, angle, velocity, _) = obstacle.pose(1.0);
+        assert!((position - vec2(0.0, 140.0)).length() < 1e-3);
+        assert_eq!(angle, 0.0);
+        assert!(velocity.length() < 1e-3);
+
+        // Halfway through, it's back in the middle and moving the other way.
+        let (position, _, velocity, _) = obstacle.pose(2.0);
+        assert!((position - vec2(0.0, 100.0)).length() < 1e-3);
+        assert!(velocity.y < 0.0);
+    }
+}
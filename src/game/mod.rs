@@ -0,0 +1,395 @@
+//! Pong gameplay.
+
+pub mod ai;
+pub mod arena;
+pub mod ball;
+pub mod broadcast;
+pub mod event;
+pub mod goal_replay;
+pub mod history;
+pub mod hud;
+pub mod input;
+pub mod layout;
+pub mod mode;
+pub mod netplay;
+pub mod powerup;
+pub mod replay;
+pub mod rules;
+pub mod serve;
+pub mod sfx;
+pub mod stats;
+
+use avian2d::math::Vector;
+
+use crate::core::input::Controls;
+use crate::game::ai::CpuController;
+use crate::game::arena::ArenaConfig;
+use crate::game::event::GoalScored;
+use crate::game::input::PaddleAction;
+use crate::game::input::PaddleInput;
+use crate::game::layout::add_obstacles;
+use crate::game::mode::MatchSetup;
+use crate::game::netplay::NetSession;
+use crate::game::rules::Lives;
+use crate::game::rules::MatchConfig;
+use crate::game::serve::Serve;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(DefaultFriction(Friction::new(0.)))
+        .insert_resource(DefaultRestitution(Restitution::new(1.)));
+
+    app.configure::<Score>();
+
+    app.add_plugins((
+        ai::plugin,
+        arena::plugin,
+        ball::plugin,
+        broadcast::plugin,
+        event::plugin,
+        goal_replay::plugin,
+        history::plugin,
+        hud::plugin,
+        input::plugin,
+        layout::plugin,
+        mode::plugin,
+        netplay::plugin,
+        powerup::plugin,
+        replay::plugin,
+        rules::plugin,
+        serve::plugin,
+        sfx::plugin,
+        stats::plugin,
+    ));
+
+    app.add_systems(
+        StateFlush,
+        state!(Screen::Gameplay | Screen::Replay).on_enter(
+            (
+                reset_score,
+                (add_players, add_ball, add_boundaries, add_obstacles),
+            )
+                .chain(),
+        ),
+    );
+    app.add_systems(
+        FixedUpdate,
+        state!(Screen::Gameplay | Screen::Replay)
+            .on_update(score_goal.in_set(UpdateSystems::HandleEvents)),
+    );
+}
+
+/// One of the sides of the arena, and the team of paddles that defends it.
+///
+/// Two-player and doubles matches use the left and right sides, and free-for-all matches
+/// use all four.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+#[reflect(Component)]
+pub enum Side {
+    /// The side defended by player 1, and player 3 in doubles matches.
+    Left,
+    /// The side defended by player 2, and player 4 in doubles matches.
+    Right,
+    /// The side defended by player 3 in free-for-all matches.
+    Top,
+    /// The side defended by player 4 in free-for-all matches.
+    Bottom,
+}
+
+impl Side {
+    pub const ALL: [Self; 4] = [Self::Left, Self::Right, Self::Top, Self::Bottom];
+
+    /// The side across the arena.
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+        }
+    }
+
+    pub fn player_name(self) -> &'static str {
+        match self {
+            Self::Left => "Player 1",
+            Self::Right => "Player 2",
+            Self::Top => "Player 3",
+            Self::Bottom => "Player 4",
+        }
+    }
+
+    /// Whether this side's paddle lies flat and moves left and right.
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Self::Top | Self::Bottom)
+    }
+
+    /// The position of this side in [`Self::ALL`], and in per-side arrays.
+    pub fn index(self) -> usize {
+        match self {
+            Self::Left => 0,
+            Self::Right => 1,
+            Self::Top => 2,
+            Self::Bottom => 3,
+        }
+    }
+
+    /// The position of this side in arrays that only hold the left and right sides,
+    /// or `None` for the top and bottom.
+    pub fn pair_index(self) -> Option<usize> {
+        match self {
+            Self::Left => Some(0),
+            Self::Right => Some(1),
+            Self::Top | Self::Bottom => None,
+        }
+    }
+
+    /// The unit vector from the center of the arena toward this side's goal.
+    pub fn direction(self) -> Vec2 {
+        match self {
+            Self::Left => Vec2::NEG_X,
+            Self::Right => Vec2::X,
+            Self::Top => Vec2::Y,
+            Self::Bottom => Vec2::NEG_Y,
+        }
+    }
+}
+
+#[derive(Resource, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct Score {
+    pub player1: u32,
+    pub player2: u32,
+}
+
+impl Configure for Score {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl Score {
+    /// Only the left and right sides score points. Free-for-all matches count [`Lives`] instead.
+    pub fn get(&self, side: Side) -> u32 {
+        match side {
+            Side::Left => self.player1,
+            Side::Right => self.player2,
+            Side::Top | Side::Bottom => 0,
+        }
+    }
+
+    pub fn add_point(&mut self, side: Side) {
+        match side {
+            Side::Left => self.player1 += 1,
+            Side::Right => self.player2 += 1,
+            Side::Top | Side::Bottom => {},
+        }
+    }
+
+    /// The side with more points, if any.
+    pub fn leader(&self) -> Option<Side> {
+        match self.player1.cmp(&self.player2) {
+            std::cmp::Ordering::Greater => Some(Side::Left),
+            std::cmp::Ordering::Less => Some(Side::Right),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// The point difference between the two sides.
+    pub fn lead(&self) -> u32 {
+        self.player1.abs_diff(self.player2)
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_score(mut score: ResMut<Score>) {
+    *score = default();
+}
+
+/// A paddle, on the team given by its [`Side`] and in the row given by its [`Slot`].
+#[derive(Component)]
+pub struct Player;
+
+/// Which of a team's paddles this is. Each side has one paddle, or two in doubles matches.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+#[reflect(Component)]
+pub enum Slot {
+    /// The paddle guarding the goal.
+    Back,
+    /// The paddle further up the arena, in doubles matches.
+    Front,
+}
+
+#[derive(Component)]
+pub struct Ball;
+
+/// One of the four walls around the arena.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Boundary {
+    Top,
+    Bottom,
+    /// The goal behind player 1.
+    Left,
+    /// The goal behind player 2.
+    Right,
+}
+
+impl Boundary {
+    pub const ALL: [Self; 4] = [Self::Top, Self::Bottom, Self::Left, Self::Right];
+
+    /// The area covered by this wall around an arena of the given size.
+    pub fn rect(self, area: Vec2, width: f32) -> Rect {
+        let half = area / 2.0 + width / 2.0;
+        let (center, size) = match self {
+            Self::Top => (vec2(0.0, half.y), vec2(area.x, width)),
+            Self::Bottom => (vec2(0.0, -half.y), vec2(area.x, width)),
+            Self::Left => (vec2(-half.x, 0.0), vec2(width, area.y)),
+            Self::Right => (vec2(half.x, 0.0), vec2(width, area.y)),
+        };
+        Rect::from_center_size(center, size)
+    }
+
+    /// The side whose goal is behind this wall.
+    ///
+    /// It only counts as a goal while that side's paddle is in the arena.
+    pub fn goal_side(self) -> Side {
+        match self {
+            Self::Left => Side::Left,
+            Self::Right => Side::Right,
+            Self::Top => Side::Top,
+            Self::Bottom => Side::Bottom,
+        }
+    }
+}
+
+fn add_players(
+    mut commands: Commands,
+    config: ConfigRef<ArenaConfig>,
+    setup: Res<MatchSetup>,
+    session: Option<Res<NetSession>>,
+    controls: Res<Controls>,
+    gamepad_query: Query<Entity, With<Gamepad>>,
+) {
+    let config = r!(config.get());
+    let mut gamepads = gamepad_query.iter();
+
+    for (player, &(side, slot)) in setup.paddles().iter().enumerate() {
+        let size = config.paddle_size(side);
+        let axes = if side.is_horizontal() {
+            LockedAxes::ALL_LOCKED.unlock_translation_x()
+        } else {
+            LockedAxes::ALL_LOCKED.unlock_translation_y()
+        };
+        let mut entity = commands.spawn((
+            Name::new(format!("Player{}", player + 1)),
+            Player {},
+            side,
+            slot,
+            Collider::rectangle(size.x, size.y),
+            Transform::from_translation(config.paddle_position(side, slot).extend(0.)),
+            RigidBody::Kinematic,
+            LinearVelocity::default(),
+            axes,
+            Sprite::from_color(Srgba::from_vec3(Vec3::splat(0.5)), size),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+
+        // Give each human player their own controls, and their own gamepad if there's one left.
+        if setup.is_cpu(player) {
+            entity.insert(CpuController::new(setup.difficulty));
+        } else if session.as_ref().is_some_and(|x| x.local_side != side) {
+            // The other player's inputs arrive over the network.
+            entity.insert(PaddleInput::default());
+        } else {
+            // A player on their own machine uses the first player's controls.
+            let controls_player = if session.is_some() { 0 } else { player };
+            entity.insert((
+                PaddleAction::input_map(&controls, controls_player, side, gamepads.next()),
+                ActionState::<PaddleAction>::default(),
+                PaddleInput::default(),
+            ));
+        }
+    }
+}
+
+/// Spawns the ball at rest. It's launched by the [`Serve`].
+fn add_ball(mut commands: Commands, config: ConfigRef<ArenaConfig>) {
+    let radius = r!(config.get()).ball_radius;
+    commands.spawn(ball_bundle(radius));
+}
+
+/// A ball at rest in the center of the arena.
+fn ball_bundle(radius: f32) -> impl Bundle {
+    (
+        Name::new("Ball"),
+        RigidBody::Dynamic,
+        Collider::circle(radius),
+        LinearVelocity::ZERO,
+        Sprite::from_color(Srgba::from_vec3(Vec3::splat(0.5)), Vec2::splat(radius * 2.)),
+        CollisionEventsEnabled,
+        Ball {},
+        DespawnOnExitState::<Screen>::Recursive,
+    )
+}
+
+fn add_boundaries(mut commands: Commands, config: ConfigRef<ArenaConfig>) {
+    let config = r!(config.get());
+
+    for boundary in Boundary::ALL {
+        let rect = boundary.rect(config.arena_size, config.boundary_width);
+        commands.spawn((
+            Name::new(format!("Boundary{boundary:?}")),
+            boundary,
+            RigidBody::Static,
+            Collider::rectangle(rect.width(), rect.height()),
+            Transform::from_translation(rect.center().extend(0.)),
+            Sprite::from_color(Srgba::from_vec3(Vec3::new(0.5, 0.25, 0.25)), rect.size()),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+}
+
+/// Award the point or take a life, and reset the ball for the next serve if it was the
+/// last one in play.
+#[cfg_attr(feature = "native_dev", hot)]
+fn score_goal(
+    mut commands: Commands,
+    mut goal_events: EventReader<GoalScored>,
+    config: ConfigRef<MatchConfig>,
+    setup: Res<MatchSetup>,
+    mut score: ResMut<Score>,
+    mut lives: ResMut<Lives>,
+    mut serve: ResMut<Serve>,
+    mut ball_query: Query<(&mut Transform, &mut LinearVelocity), With<Ball>>,
+    paddle_query: Query<(Entity, &Side), With<Player>>,
+) {
+    let config = r!(config.get());
+    let mut balls_in_play = ball_query.iter().count();
+    for event in goal_events.read() {
+        if setup.is_free_for_all() {
+            // An eliminated side's paddle is removed, so its goal becomes a wall.
+            if lives.lose(event.conceded) {
+                for (entity, &side) in &paddle_query {
+                    if side == event.conceded {
+                        commands.entity(entity).despawn();
+                    }
+                }
+            }
+        } else {
+            score.add_point(event.scorer);
+        }
+
+        // Extra balls leave the arena when they score.
+        if balls_in_play > 1 {
+            commands.entity(event.ball).despawn();
+            balls_in_play -= 1;
+            continue;
+        }
+
+        let (mut transform, mut velocity) = c!(ball_query.get_mut(event.ball));
+        transform.translation = Vec3::ZERO;
+        velocity.0 = Vector::ZERO;
+        serve.after_goal(event.conceded, config, &lives);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::core::rng::GameRng;
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::Slot;
+use crate::game::ai::CpuDifficulty;
+use crate::game::rules::Lives;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<MatchSetup>();
+}
+
+/// Who controls the paddles.
+#[derive(Reflect, Copy, Clone, Default, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum PlayMode {
+    /// One human player against a computer-controlled paddle.
+    #[default]
+    VersusCpu,
+    /// Two human players sharing one device.
+    LocalVersus,
+    /// Two human players on different machines, connected by a
+    /// [`NetSession`](crate::game::netplay::NetSession).
+    Lan,
+    /// Four paddles, one on each side, with any mix of human and CPU players.
+    /// Each side has a few lives, and the last side left wins.
+    FreeForAll,
+    /// Two paddles on each of the left and right sides, one guarding the goal and one
+    /// further forward, with any mix of human and CPU players.
+    Doubles,
+}
+
+impl PlayMode {
+    /// Every mode, including those that need another machine.
+    pub const EVERY: [Self; 5] = [
+        Self::VersusCpu,
+        Self::LocalVersus,
+        Self::Lan,
+        Self::Doubles,
+        Self::FreeForAll,
+    ];
+
+    /// The modes that can be chosen for a match on one device.
+    pub const ALL: [Self; 4] = [
+        Self::VersusCpu,
+        Self::LocalVersus,
+        Self::Doubles,
+        Self::FreeForAll,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::VersusCpu => "1P vs CPU",
+            Self::LocalVersus => "2P local",
+            Self::Lan => "2P LAN",
+            Self::FreeForAll => "4P free-for-all",
+            Self::Doubles => "2v2 doubles",
+        }
+    }
+}
+
+/// How many balls are in play.
+#[derive(Reflect, Copy, Clone, Default, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum BallRule {
+    /// One ball, served after every goal.
+    #[default]
+    Single,
+    /// Extra balls join as the rally goes on, and leave when they score.
+    MultiBall,
+}
+
+impl BallRule {
+    pub const ALL: [Self; 2] = [Self::Single, Self::MultiBall];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Single => "Single",
+            Self::MultiBall => "Multi-ball",
+        }
+    }
+}
+
+/// The options chosen before starting a match.
+#[derive(Resource, Reflect, Copy, Clone, Debug, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct MatchSetup {
+    pub mode: PlayMode,
+    pub difficulty: CpuDifficulty,
+    /// The number of human players in a free-for-all or doubles match. They take the first
+    /// paddles in [`Self::paddles`] order, and the CPU takes the rest.
+    #[serde(default)]
+    pub human_players: usize,
+    /// Defaults to one ball for replays saved before multi-ball existed.
+    #[serde(default)]
+    pub balls: BallRule,
+    /// The index of the arena layout in
+    /// [`LayoutConfig::layouts`](crate::game::layout::LayoutConfig::layouts).
+    /// Defaults to the first layout for replays saved before layouts existed.
+    #[serde(default)]
+    pub layout: usize,
+    /// The seed for the match's [`GameRng`], or `None` for a random seed.
+    pub seed: Option<u64>,
+}
+
+impl Default for MatchSetup {
+    fn default() -> Self {
+        Self {
+            mode: default(),
+            difficulty: default(),
+            human_players: 1,
+            balls: default(),
+            layout: 0,
+            seed: None,
+        }
+    }
+}
+
+impl Configure for MatchSetup {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            state!(Screen::Gameplay | Screen::Replay).on_enter(seed_game_rng),
+        );
+    }
+}
+
+impl MatchSetup {
+    /// The sides with a paddle in this match.
+    pub fn sides(&self) -> &'static [Side] {
+        if self.is_free_for_all() {
+            &Side::ALL
+        } else {
+            &Side::ALL[..2]
+        }
+    }
+
+    /// The team and row of each paddle in this match, in player order.
+    pub fn paddles(&self) -> &'static [(Side, Slot)] {
+        match self.mode {
+            PlayMode::FreeForAll => &[
+                (Side::Left, Slot::Back),
+                (Side::Right, Slot::Back),
+                (Side::Top, Slot::Back),
+                (Side::Bottom, Slot::Back),
+            ],
+            PlayMode::Doubles => &[
+                (Side::Left, Slot::Back),
+                (Side::Right, Slot::Back),
+                (Side::Left, Slot::Front),
+                (Side::Right, Slot::Front),
+            ],
+            _ => &[(Side::Left, Slot::Back), (Side::Right, Slot::Back)],
+        }
+    }
+
+    /// The player controlling the given paddle, if it's in this match.
+    pub fn player_index(&self, side: Side, slot: Slot) -> Option<usize> {
+        self.paddles().iter().position(|&x| x == (side, slot))
+    }
+
+    /// Whether the given player's paddle is computer-controlled.
+    pub fn is_cpu(&self, player: usize) -> bool {
+        match self.mode {
+            PlayMode::VersusCpu => player == 1,
+            PlayMode::LocalVersus | PlayMode::Lan => false,
+            PlayMode::FreeForAll | PlayMode::Doubles => player >= self.human_players,
+        }
+    }
+
+    /// Whether any paddle in this match is computer-controlled.
+    pub fn has_cpu(&self) -> bool {
+        (0..self.paddles().len()).any(|player| self.is_cpu(player))
+    }
+
+    pub fn is_free_for_all(&self) -> bool {
+        self.mode == PlayMode::FreeForAll
+    }
+
+    /// Whether the match has four players, who can each be human or CPU.
+    pub fn is_four_player(&self) -> bool {
+        self.paddles().len() == 4
+    }
+
+    /// Whether extra balls can join the match.
+    pub fn is_multi_ball(setup: Res<Self>) -> bool {
+        setup.balls == BallRule::MultiBall
+    }
+
+    /// The display name for the given player.
+    pub fn player_name(&self, player: usize) -> String {
+        if self.is_cpu(player) {
+            "CPU".to_string()
+        } else {
+            format!("Player {}", player + 1)
+        }
+    }
+
+    /// The display name for the team on the given side.
+    pub fn side_name(&self, side: Side) -> String {
+        self.paddles()
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.0 == side)
+            .map(|(player, _)| self.player_name(player))
+            .collect::<Vec<_>>()
+            .join(" & ")
+    }
+
+    /// One side's score, or its lives in free-for-all matches.
+    pub fn side_value(&self, score: &Score, lives: &Lives, side: Side) -> u32 {
+        if self.is_free_for_all() {
+            lives.get(side)
+        } else {
+            score.get(side)
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+pub fn seed_game_rng(setup: Res<MatchSetup>, mut rng: ResMut<GameRng>) {
+    rng.reseed(setup.seed.unwrap_or_else(random));
+}
+
+/// Returns the next item in `all` after `current`, wrapping around.
+pub fn cycle<T: Copy + PartialEq>(all: &[T], current: T, step: isize) -> T {
+    let i = all.iter().position(|&x| x == current).unwrap_or(0) as isize;
+    all[(i + step).rem_euclid(all.len() as isize) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doubles_teams() {
+        let setup = MatchSetup {
+            mode: PlayMode::Doubles,
+            human_players: 3,
+            ..default()
+        };
+
+        // Humans take the back paddles first, then the front paddles.
+        assert_eq!(setup.player_index(Side::Left, Slot::Front), Some(2));
+        assert_eq!(setup.player_index(Side::Top, Slot::Back), None);
+        assert_eq!(setup.side_name(Side::Left), "Player 1 & Player 3");
+        assert_eq!(setup.side_name(Side::Right), "Player 2 & CPU");
+        assert!(setup.has_cpu());
+    }
+}
@@ -0,0 +1,816 @@
+use std::collections::VecDeque;
+use std::hash::Hasher as _;
+use std::io;
+use std::net::IpAddr;
+use std::net::SocketAddr;
+use std::net::ToSocketAddrs;
+use std::net::UdpSocket;
+
+use bevy::app::FixedMain;
+
+use crate::core::rng::GameRng;
+use crate::game::Ball;
+use crate::game::Player;
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::ball::Rally;
+use crate::game::event::GoalScored;
+use crate::game::event::MatchWon;
+use crate::game::event::PaddleHit;
+use crate::game::event::ServeLaunched;
+use crate::game::event::WallHit;
+use crate::game::input::PaddleInput;
+use crate::game::input::move_paddles;
+use crate::game::input::record_paddle_input;
+use crate::game::mode::BallRule;
+use crate::game::mode::MatchSetup;
+use crate::game::mode::PlayMode;
+use crate::game::powerup::PowerUps;
+use crate::game::replay::ReplayRecorder;
+use crate::game::rules::MatchClock;
+use crate::game::rules::MatchResult;
+use crate::game::serve::Serve;
+use crate::game::stats::MatchStats;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+use crate::screen::fade::fade_out;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(NetSession, IsNetStatus)>();
+}
+
+/// The UDP port that LAN games are hosted on.
+pub const DEFAULT_PORT: u16 = 7777;
+/// The number of ticks between pressing a key and the paddle moving, to hide latency.
+const INPUT_DELAY: u32 = 2;
+/// The number of ticks the match can run ahead of the other player's inputs.
+const MAX_PREDICTION: u32 = 8;
+/// The number of recent ticks that can be rolled back to.
+const SNAPSHOT_COUNT: usize = 64;
+/// The most inputs sent in one packet. Every packet repeats the inputs the other player
+/// hasn't acknowledged yet, in case some were lost.
+const MAX_PACKET_INPUTS: usize = 64;
+/// The number of ticks between desync checks.
+const CHECKSUM_INTERVAL: u32 = 60;
+/// The number of seconds without a packet before the connection is considered lost.
+const TIMEOUT_SECS: f32 = 5.0;
+
+#[derive(Serialize, Deserialize, Debug)]
+enum Message {
+    /// Sent by the joining player until the host answers.
+    Hello,
+    /// Sent by the host in answer to [`Message::Hello`].
+    Welcome { seed: u64 },
+    /// The sender's paddle inputs, starting from a tick, and the number of the receiver's
+    /// inputs the sender has received so far.
+    Inputs {
+        start: u32,
+        inputs: Vec<i8>,
+        ack: u32,
+    },
+    /// A checksum of the game state at the start of a tick.
+    Checksum { tick: u32, checksum: u64 },
+}
+
+/// How a [`NetSession`] is doing.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NetStatus {
+    /// Waiting for the other player to connect.
+    Waiting,
+    Connected,
+    /// The two game states no longer match, starting from the given tick.
+    Desynced(u32),
+    /// The other player stopped responding.
+    Disconnected,
+}
+
+/// A peer-to-peer match over UDP with input delay and rollback.
+///
+/// Each tick runs with the local player's input from [`INPUT_DELAY`] ticks earlier and
+/// the other player's input if it has arrived, or else a guess that they're still
+/// pressing the same thing. When a guess turns out wrong, the match rolls back to that
+/// tick and plays forward again with the real inputs.
+///
+/// The physics engine's own contact cache isn't rolled back, so the two machines can
+/// still drift apart in rare cases. Both players send checksums of the game state to
+/// detect this.
+#[derive(Resource)]
+pub struct NetSession {
+    socket: UdpSocket,
+    peer: Option<SocketAddr>,
+    /// The match seed, chosen by the host.
+    seed: u64,
+    pub local_side: Side,
+    pub status: NetStatus,
+    /// The next tick to simulate.
+    pub tick: u32,
+    /// The local player's inputs by tick.
+    local_inputs: Vec<i8>,
+    /// The other player's inputs by tick, as far as they've arrived.
+    remote_inputs: Vec<i8>,
+    /// The number of the local player's inputs the other player has received.
+    remote_ack: usize,
+    /// The guesses used for the other player's inputs that haven't arrived yet.
+    predictions: HashMap<u32, i8>,
+    /// The earliest tick that was simulated with a wrong guess.
+    rollback_to: Option<u32>,
+    /// The number of ticks simulated again in the most recent rollback.
+    pub last_rollback: u32,
+    snapshots: VecDeque<(u32, GameSnapshot)>,
+    /// The next tick to send a checksum for.
+    next_checksum: u32,
+    local_checksums: HashMap<u32, u64>,
+    remote_checksums: HashMap<u32, u64>,
+    /// Whether the end of the match has been confirmed by both players' inputs.
+    match_ended: bool,
+    /// When the last packet arrived, in seconds since startup.
+    last_heard: f32,
+}
+
+impl Configure for NetSession {
+    fn configure(app: &mut App) {
+        app.add_systems(
+            StateFlush,
+            Screen::Gameplay.on_exit(end_net_session.run_if(resource_exists::<Self>)),
+        );
+        app.add_systems(
+            Update,
+            (
+                receive_net_messages
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(resource_exists::<Self>),
+                Screen::Gameplay.on_update(
+                    (
+                        roll_back_net_session,
+                        confirm_net_match_end,
+                        check_net_desync,
+                        check_net_timeout,
+                        send_net_inputs,
+                        sync_net_time,
+                    )
+                        .chain()
+                        .in_set(UpdateSystems::SyncLate)
+                        .run_if(resource_exists::<Self>),
+                ),
+            ),
+        );
+        app.add_systems(
+            FixedFirst,
+            Screen::Gameplay.on_update(save_net_snapshot.run_if(resource_exists::<Self>)),
+        );
+        app.add_systems(
+            FixedUpdate,
+            Screen::Gameplay.on_update(
+                apply_net_input
+                    .in_set(UpdateSystems::RecordInput)
+                    .after(record_paddle_input)
+                    .before(move_paddles)
+                    .run_if(resource_exists::<Self>),
+            ),
+        );
+    }
+}
+
+impl NetSession {
+    /// Wait for another player to join on the given port.
+    pub fn host(port: u16) -> io::Result<Self> {
+        Self::new(("0.0.0.0", port), None, Side::Left)
+    }
+
+    /// Join the host at the given address.
+    pub fn join(host: SocketAddr) -> io::Result<Self> {
+        Self::new(("0.0.0.0", 0), Some(host), Side::Right)
+    }
+
+    fn new(
+        addr: impl ToSocketAddrs,
+        peer: Option<SocketAddr>,
+        local_side: Side,
+    ) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            peer,
+            seed: random(),
+            local_side,
+            status: NetStatus::Waiting,
+            tick: 0,
+            // Both players start with no input during the input delay.
+            local_inputs: vec![0; INPUT_DELAY as usize],
+            remote_inputs: vec![0; INPUT_DELAY as usize],
+            remote_ack: 0,
+            predictions: default(),
+            rollback_to: None,
+            last_rollback: 0,
+            snapshots: default(),
+            next_checksum: CHECKSUM_INTERVAL,
+            local_checksums: default(),
+            remote_checksums: default(),
+            match_ended: false,
+            last_heard: 0.0,
+        })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.socket.local_addr().ok()
+    }
+
+    /// Whether the match is waiting for the other player's inputs to catch up.
+    pub fn is_stalled(&self) -> bool {
+        self.tick >= self.remote_inputs.len() as u32 + MAX_PREDICTION
+    }
+
+    fn send(&self, message: &Message) {
+        let peer = rq!(self.peer);
+        let text = r!(ron::to_string(message));
+        // Packets can be lost anyway, so errors are ignored.
+        let _ = self.socket.send_to(text.as_bytes(), peer);
+    }
+
+    fn receive(&self) -> Option<(Message, SocketAddr)> {
+        let mut buffer = [0; 1024];
+        loop {
+            let (len, from) = self.socket.recv_from(&mut buffer).ok()?;
+            let message = std::str::from_utf8(&buffer[..len])
+                .ok()
+                .and_then(|text| ron::from_str(text).ok());
+            if let Some(message) = message {
+                return Some((message, from));
+            }
+        }
+    }
+
+    fn receive_inputs(&mut self, start: u32, inputs: &[i8], ack: u32) {
+        // Packets can arrive out of order, so keep the furthest acknowledgement.
+        self.remote_ack = self.remote_ack.max(ack as usize);
+        for (tick, &input) in (start..).zip(inputs) {
+            if tick as usize != self.remote_inputs.len() {
+                continue;
+            }
+            self.remote_inputs.push(input);
+
+            // Roll back if this tick was simulated with the wrong guess.
+            if self.predictions.remove(&tick).is_some_and(|x| x != input) {
+                self.rollback_to = Some(self.rollback_to.map_or(tick, |x| x.min(tick)));
+            }
+        }
+    }
+
+    /// The inputs for the left and right paddles on the next tick.
+    fn next_inputs(&mut self, local_input: i8) -> [i8; 2] {
+        let tick = self.tick as usize;
+        // Skip recording when playing forward after a rollback.
+        if self.local_inputs.len() == tick + INPUT_DELAY as usize {
+            self.local_inputs.push(local_input);
+        }
+
+        let local = self.local_inputs.get(tick).copied().unwrap_or_default();
+        let remote = match self.remote_inputs.get(tick) {
+            Some(&remote) => remote,
+            None => {
+                let guess = self.remote_inputs.last().copied().unwrap_or_default();
+                self.predictions.insert(self.tick, guess);
+                guess
+            },
+        };
+        self.tick += 1;
+
+        match self.local_side {
+            Side::Left => [local, remote],
+            _ => [remote, local],
+        }
+    }
+
+    fn snapshot(&self, tick: u32) -> Option<&GameSnapshot> {
+        self.snapshots
+            .iter()
+            .find(|(x, _)| *x == tick)
+            .map(|(_, snapshot)| snapshot)
+    }
+}
+
+/// This machine's address on the local network, to show to the joining player.
+pub fn local_ip() -> Option<IpAddr> {
+    #[cfg(feature = "native")]
+    return if_addrs::get_if_addrs()
+        .ok()?
+        .into_iter()
+        .find(|x| !x.is_loopback() && x.ip().is_ipv4())
+        .map(|x| x.ip());
+    #[cfg(not(feature = "native"))]
+    None
+}
+
+/// Everything that changes during a match, for rolling back to an earlier tick.
+#[derive(Clone)]
+struct GameSnapshot {
+    bodies: Vec<(
+        Entity,
+        Transform,
+        Position,
+        Rotation,
+        LinearVelocity,
+        AngularVelocity,
+    )>,
+    score: Score,
+    serve: Serve,
+    rally: Rally,
+    stats: MatchStats,
+    power_ups: PowerUps,
+    clock: MatchClock,
+    rng: GameRng,
+    result: Option<MatchResult>,
+}
+
+impl GameSnapshot {
+    fn capture(world: &mut World) -> Option<Self> {
+        let bodies = world
+            .query_filtered::<(
+                Entity,
+                &Transform,
+                &Position,
+                &Rotation,
+                &LinearVelocity,
+                &AngularVelocity,
+            ), Or<(With<Ball>, With<Player>)>>()
+            .iter(world)
+            .map(|(entity, &a, &b, &c, &d, &e)| (entity, a, b, c, d, e))
+            .collect();
+
+        Some(Self {
+            bodies,
+            score: *world.get_resource::<Score>()?,
+            serve: world.get_resource::<Serve>()?.clone(),
+            rally: world.get_resource::<Rally>()?.clone(),
+            stats: world.get_resource::<MatchStats>()?.clone(),
+            power_ups: world.get_resource::<PowerUps>()?.clone(),
+            clock: world.get_resource::<MatchClock>()?.clone(),
+            rng: world.get_resource::<GameRng>()?.clone(),
+            result: world.get_resource::<MatchResult>().copied(),
+        })
+    }
+
+    fn restore(&self, world: &mut World) {
+        for &(entity, transform, position, rotation, velocity, angular_velocity) in &self.bodies {
+            c!(world.get_entity_mut(entity)).insert((
+                transform,
+                position,
+                rotation,
+                velocity,
+                angular_velocity,
+            ));
+        }
+        // Avoid triggering change detection if the score didn't change.
+        r!(world.get_resource_mut::<Score>()).set_if_neq(self.score);
+        world.insert_resource(self.serve.clone());
+        world.insert_resource(self.rally.clone());
+        world.insert_resource(self.stats.clone());
+        world.insert_resource(self.power_ups.clone());
+        world.insert_resource(self.clock.clone());
+        world.insert_resource(self.rng.clone());
+        // Undo a match end that was decided on an abandoned tick.
+        match self.result {
+            Some(result) => world.insert_resource(result),
+            None => {
+                world.remove_resource::<MatchResult>();
+            },
+        }
+
+        // Drop events from the abandoned ticks.
+        r!(world.get_resource_mut::<Events<GoalScored>>()).clear();
+        r!(world.get_resource_mut::<Events<PaddleHit>>()).clear();
+        r!(world.get_resource_mut::<Events<WallHit>>()).clear();
+        r!(world.get_resource_mut::<Events<ServeLaunched>>()).clear();
+        r!(world.get_resource_mut::<Events<MatchWon>>()).clear();
+    }
+
+    fn checksum(&self) -> u64 {
+        // Entity order can differ between machines, so combine each body's hash in any order.
+        let mut checksum = 0u64;
+        for (_, _, position, _, velocity, _) in &self.bodies {
+            let mut hasher = std::hash::DefaultHasher::new();
+            for x in [position.x, position.y, velocity.x, velocity.y] {
+                hasher.write_u32(x.to_bits());
+            }
+            checksum = checksum.wrapping_add(hasher.finish());
+        }
+
+        let mut hasher = std::hash::DefaultHasher::new();
+        hasher.write_u32(self.score.player1);
+        hasher.write_u32(self.score.player2);
+        hasher.write_u32(self.rally.hits);
+        hasher.write_usize(self.power_ups.pickups.len());
+        hasher.write_usize(self.power_ups.active.len());
+        hasher.write_u128(self.clock.0.as_nanos());
+        hasher.write_u64(self.rng.clone().next_u64());
+        checksum.wrapping_add(hasher.finish())
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn receive_net_messages(
+    time: Res<Time<Real>>,
+    mut session: ResMut<NetSession>,
+    mut setup: ResMut<MatchSetup>,
+) {
+    while let Some((message, from)) = session.receive() {
+        // Ignore anyone else once connected, and anything but the handshake before then.
+        match session.peer {
+            Some(peer) => cq!(peer == from),
+            None => cq!(matches!(message, Message::Hello | Message::Welcome { .. })),
+        }
+        session.last_heard = time.elapsed_secs();
+
+        match message {
+            // Only the host answers, and it keeps answering in case a welcome is lost.
+            Message::Hello if session.local_side == Side::Left => {
+                session.peer = Some(from);
+                session.send(&Message::Welcome { seed: session.seed });
+            },
+            Message::Welcome { seed } if session.status == NetStatus::Waiting => {
+                session.seed = seed;
+            },
+            Message::Inputs { start, inputs, ack } => {
+                session.receive_inputs(start, &inputs, ack);
+                continue;
+            },
+            Message::Checksum { tick, checksum } => {
+                session.remote_checksums.insert(tick, checksum);
+                continue;
+            },
+            _ => continue,
+        }
+
+        // The match starts on the first hello or welcome.
+        if session.status == NetStatus::Waiting {
+            info!("Connected to {from}");
+            session.status = NetStatus::Connected;
+            *setup = MatchSetup {
+                mode: PlayMode::Lan,
+                // Balls that join or leave mid-rally can't be rolled back.
+                balls: BallRule::Single,
+                // The layout isn't chosen in the LAN menus, so both machines use the first.
+                layout: 0,
+                seed: Some(session.seed),
+                ..*setup
+            };
+        }
+    }
+
+    // Keep knocking until the host answers.
+    if session.status == NetStatus::Waiting {
+        session.send(&Message::Hello);
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn end_net_session(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    commands.remove_resource::<NetSession>();
+    time.unpause();
+}
+
+fn save_net_snapshot(world: &mut World) {
+    let snapshot = r!(GameSnapshot::capture(world));
+    let mut session = r!(world.get_resource_mut::<NetSession>());
+    let tick = session.tick;
+    session.snapshots.retain(|(x, _)| *x < tick);
+    session.snapshots.push_back((tick, snapshot));
+    while session.snapshots.len() > SNAPSHOT_COUNT {
+        session.snapshots.pop_front();
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_net_input(
+    mut session: ResMut<NetSession>,
+    mut paddle_query: Query<(&Side, &mut PaddleInput), With<Player>>,
+) {
+    let local_input = paddle_query
+        .iter()
+        .find(|(side, _)| **side == session.local_side)
+        .map(|(_, input)| input.0)
+        .unwrap_or_default();
+    let inputs = session.next_inputs(local_input);
+
+    for (&side, mut input) in &mut paddle_query {
+        let input_value = inputs[cq!(side.pair_index())];
+        input.set_if_neq(PaddleInput(input_value));
+    }
+}
+
+/// Play forward again from the earliest wrong guess, all at once.
+fn roll_back_net_session(world: &mut World) {
+    let mut session = r!(world.get_resource_mut::<NetSession>());
+    let from = rq!(session.rollback_to.take());
+    let to = session.tick;
+    let Some(snapshot) = session.snapshot(from).cloned() else {
+        warn!("Can't roll back to tick {from}, it's too far in the past");
+        return;
+    };
+    session.tick = from;
+    session.last_rollback = to - from;
+
+    snapshot.restore(world);
+    if let Some(replay) = r!(world.get_resource_mut::<ReplayRecorder>()).0.as_mut() {
+        replay.truncate(from);
+    }
+
+    // Run the fixed timestep schedule directly, as if that much time had passed.
+    let time = *r!(world.get_resource::<Time>());
+    for _ in from..to {
+        let fixed_time = r!(world.get_resource::<Time<Fixed>>()).as_generic();
+        *r!(world.get_resource_mut::<Time>()) = fixed_time;
+        world.run_schedule(FixedMain);
+    }
+    *r!(world.get_resource_mut::<Time>()) = time;
+}
+
+/// End the match once the tick that decided it can no longer be rolled back.
+#[cfg_attr(feature = "native_dev", hot)]
+fn confirm_net_match_end(
+    mut commands: Commands,
+    mut session: ResMut<NetSession>,
+    result: Option<Res<MatchResult>>,
+) {
+    rq!(!session.match_ended);
+    // The state after every tick with both players' inputs is final.
+    let confirmed = session.remote_inputs.len() as u32;
+    let is_decided = if confirmed >= session.tick {
+        result.is_some()
+    } else {
+        r!(session.snapshot(confirmed)).result.is_some()
+    };
+    rq!(is_decided);
+
+    session.match_ended = true;
+    commands.spawn(fade_out(Screen::Results));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn check_net_desync(mut session: ResMut<NetSession>) {
+    // A tick's state is final once both players' inputs before it have arrived.
+    let last_final = (session.remote_inputs.len() as u32).min(session.tick.saturating_sub(1));
+    while session.next_checksum <= last_final {
+        let tick = session.next_checksum;
+        session.next_checksum += CHECKSUM_INTERVAL;
+        let checksum = cq!(session.snapshot(tick)).checksum();
+        session.local_checksums.insert(tick, checksum);
+        session.send(&Message::Checksum { tick, checksum });
+    }
+
+    let session = &mut *session;
+    for (tick, remote) in std::mem::take(&mut session.remote_checksums) {
+        let Some(local) = session.local_checksums.get(&tick) else {
+            // Compare later, after this tick's checksum has been computed.
+            session.remote_checksums.insert(tick, remote);
+            continue;
+        };
+        if *local != remote && session.status == NetStatus::Connected {
+            error!("Desync detected at tick {tick}");
+            session.status = NetStatus::Desynced(tick);
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn check_net_timeout(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut session: ResMut<NetSession>,
+) {
+    rq!(matches!(
+        session.status,
+        NetStatus::Connected | NetStatus::Desynced(_),
+    ));
+    rq!(time.elapsed_secs() - session.last_heard > TIMEOUT_SECS);
+
+    warn!("Lost connection to the other player");
+    session.status = NetStatus::Disconnected;
+    commands.spawn(fade_out(Screen::Title));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn send_net_inputs(session: Res<NetSession>) {
+    // Send from the oldest input the other player is missing, however long ago it was lost.
+    let start = session.remote_ack.min(session.local_inputs.len());
+    let end = session.local_inputs.len().min(start + MAX_PACKET_INPUTS);
+    session.send(&Message::Inputs {
+        start: start as u32,
+        inputs: session.local_inputs[start..end].to_vec(),
+        ack: session.remote_inputs.len() as u32,
+    });
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn sync_net_time(session: Res<NetSession>, mut time: ResMut<Time<Virtual>>) {
+    if session.is_stalled() && session.status != NetStatus::Disconnected {
+        time.pause();
+    } else {
+        time.unpause();
+    }
+}
+
+/// The label that shows the state of the connection.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsNetStatus;
+
+impl Configure for IsNetStatus {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            StateFlush,
+            Screen::Gameplay.on_enter(spawn_net_status.run_if(resource_exists::<NetSession>)),
+        );
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                update_net_status
+                    .in_set(UpdateSystems::Update)
+                    .run_if(resource_exists::<NetSession>),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_net_status(mut commands: Commands, screen_root: Res<ScreenRoot>) {
+    commands.entity(screen_root.ui).with_child((
+        Name::new("NetStatus"),
+        Node {
+            justify_content: JustifyContent::End,
+            align_items: AlignItems::End,
+            padding: UiRect::all(Vw(1.5)),
+            ..Node::DEFAULT.full_size().abs()
+        },
+        Pickable::IGNORE,
+        children![(widget::label(""), IsNetStatus)],
+    ));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_net_status(
+    session: Res<NetSession>,
+    mut label_query: Query<&mut RichText, With<IsNetStatus>>,
+) {
+    let text = match session.status {
+        NetStatus::Waiting => "Connecting...".to_string(),
+        NetStatus::Connected if session.is_stalled() => {
+            "Waiting for the other player...".to_string()
+        },
+        NetStatus::Connected => format!("LAN: rollback {}", session.last_rollback),
+        NetStatus::Desynced(tick) => format!("[b]Desync[r] at tick {tick}"),
+        NetStatus::Disconnected => "[b]Connection lost".to_string(),
+    };
+    for mut label in &mut label_query {
+        label.sections = parse_rich(&text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::input::ControlSlot;
+    use crate::game::rules::MatchConfig;
+    use crate::headless::HeadlessMatch;
+    use crate::headless::ScriptedInput;
+
+    fn press(tick: u32, slot: ControlSlot, pressed: bool) -> ScriptedInput {
+        ScriptedInput {
+            tick,
+            slot,
+            pressed,
+        }
+    }
+
+    fn session(game: &HeadlessMatch) -> &NetSession {
+        game.app.world().resource::<NetSession>()
+    }
+
+    /// A host and a joiner on loopback, connected and in gameplay.
+    fn connected_games(config: MatchConfig) -> [HeadlessMatch; 2] {
+        let host = NetSession::host(0).unwrap();
+        let port = host.local_addr().unwrap().port();
+        let joiner = NetSession::join(SocketAddr::from(([127, 0, 0, 1], port))).unwrap();
+
+        // Connect before the match starts.
+        let mut games = [host, joiner].map(|session| {
+            let mut game = HeadlessMatch::new(default()).with_config(config.clone());
+            game.app.insert_resource(session);
+            game
+        });
+        for _ in 0..10 {
+            for game in &mut games {
+                game.app.update();
+            }
+        }
+        games.map(|game| game.start())
+    }
+
+    /// The number of ticks that both players computed a checksum for, after checking
+    /// that those checksums match.
+    fn compare_checksums(host: &HeadlessMatch, joiner: &HeadlessMatch) -> usize {
+        let host_checksums = &session(host).local_checksums;
+        let joiner_checksums = &session(joiner).local_checksums;
+        let mut compared = 0;
+        for (tick, checksum) in host_checksums {
+            if let Some(other) = joiner_checksums.get(tick) {
+                assert_eq!(checksum, other, "checksums differ at tick {tick}");
+                compared += 1;
+            }
+        }
+        compared
+    }
+
+    #[test]
+    fn test_loopback_match_stays_in_sync() {
+        let [host, joiner] = connected_games(MatchConfig {
+            goal_replay_secs: 0.0,
+            ..default()
+        });
+        let mut host = host.with_script([
+            press(20, ControlSlot::Player1Up, true),
+            press(60, ControlSlot::Player1Up, false),
+        ]);
+        let mut joiner = joiner.with_script([
+            press(40, ControlSlot::Player1Down, true),
+            press(100, ControlSlot::Player1Down, false),
+        ]);
+
+        for _ in 0..600 {
+            host.step(1);
+            joiner.step(1);
+        }
+
+        assert_eq!(session(&host).status, NetStatus::Connected);
+        assert_eq!(session(&joiner).status, NetStatus::Connected);
+        assert!(session(&host).local_checksums.len() >= 5);
+        assert!(compare_checksums(&host, &joiner) > 0);
+
+        // Each player's paddle moved on both machines.
+        assert!(host.paddle_y(Side::Right) < 0.0);
+        assert!(joiner.paddle_y(Side::Left) > 0.0);
+    }
+
+    #[test]
+    fn test_loopback_match_plays_to_the_end() {
+        // The first goal wins, after a few inputs that the other machine has to predict.
+        let [host, joiner] = connected_games(MatchConfig {
+            points_to_win: 1,
+            win_by_two: false,
+            serve_countdown: 0.5,
+            goal_replay_secs: 0.0,
+            ..default()
+        });
+        let mut host = host.with_script([
+            press(10, ControlSlot::Player1Up, true),
+            press(20, ControlSlot::Player1Up, false),
+        ]);
+        let mut joiner = joiner.with_script([
+            press(15, ControlSlot::Player1Down, true),
+            press(25, ControlSlot::Player1Down, false),
+        ]);
+
+        for _ in 0..2000 {
+            host.step(1);
+            joiner.step(1);
+            if session(&host).match_ended && session(&joiner).match_ended {
+                break;
+            }
+        }
+        assert!(session(&host).match_ended);
+        assert!(session(&joiner).match_ended);
+        assert_eq!(session(&host).status, NetStatus::Connected);
+        assert_eq!(session(&joiner).status, NetStatus::Connected);
+        assert!(compare_checksums(&host, &joiner) > 0);
+
+        // Both machines agree on how the match ended.
+        let [host_result, joiner_result] =
+            [&host, &joiner].map(|game| *game.app.world().resource::<MatchResult>());
+        assert_eq!(host_result.winner, joiner_result.winner);
+        assert_eq!(host_result.score, joiner_result.score);
+        assert_eq!(host_result.duration, joiner_result.duration);
+
+        // And on the final state, compared on the latest tick both have confirmed.
+        let tick = [&host, &joiner]
+            .map(|game| {
+                let session = session(game);
+                (session.remote_inputs.len() as u32).min(session.tick - 1)
+            })
+            .into_iter()
+            .min()
+            .unwrap();
+        let [host_state, joiner_state] =
+            [&host, &joiner].map(|game| session(game).snapshot(tick).unwrap().clone());
+        assert!(host_state.result.is_some());
+        assert_eq!(host_state.score, joiner_state.score);
+        assert_eq!(host_state.clock.0, joiner_state.clock.0);
+        assert_eq!(
+            host_state.rng.clone().next_u64(),
+            joiner_state.rng.clone().next_u64(),
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use crate::core::rng::GameRng;
+use crate::game::Ball;
+use crate::game::Player;
+use crate::game::Side;
+use crate::game::Slot;
+use crate::game::arena::ArenaConfig;
+use crate::game::ball::Rally;
+use crate::game::mode::MatchSetup;
+use crate::game::serve::Serve;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        ConfigHandle<PowerUpConfig>,
+        PowerUps,
+        PickupSprite,
+        PowerUpWall,
+    )>();
+}
+
+/// Which power-ups can appear, how often, and how strong they are.
+#[derive(Asset, Reflect, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct PowerUpConfig {
+    /// The seconds of play between pickups appearing.
+    pub spawn_interval: f32,
+    /// The most pickups that can be in the arena at once.
+    pub max_pickups: usize,
+    /// The pickup radius in world units.
+    pub pickup_radius: f32,
+    /// The power-ups that can appear. An empty list turns power-ups off.
+    pub power_ups: Vec<PowerUpSpec>,
+
+    /// How much taller a paddle gets with [`PowerUpKind::BigPaddle`].
+    pub big_paddle_scale: f32,
+    /// How much faster the ball moves with [`PowerUpKind::FastBall`].
+    pub fast_ball_scale: f32,
+    /// The length of the wall added by [`PowerUpKind::ExtraWall`], in world units.
+    pub extra_wall_length: f32,
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 8.0,
+            max_pickups: 2,
+            pickup_radius: 16.0,
+            power_ups: vec![
+                PowerUpSpec::new(PowerUpKind::BigPaddle, 1.0, 8.0),
+                PowerUpSpec::new(PowerUpKind::FastBall, 1.0, 6.0),
+                PowerUpSpec::new(PowerUpKind::ReversedControls, 0.5, 5.0),
+                PowerUpSpec::new(PowerUpKind::ExtraWall, 0.5, 5.0),
+            ],
+
+            big_paddle_scale: 1.5,
+            fast_ball_scale: 1.4,
+            extra_wall_length: 200.0,
+        }
+    }
+}
+
+impl Config for PowerUpConfig {
+    const FILE: &'static str = "powerup.ron";
+}
+
+impl PowerUpConfig {
+    /// Pick a power-up at random, weighted by [`PowerUpSpec::weight`].
+    fn pick(&self, rng: &mut impl Rng) -> Option<&PowerUpSpec> {
+        let total = self
+            .power_ups
+            .iter()
+            .map(|x| x.weight.max(0.0))
+            .sum::<f32>();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0.0..total);
+        for spec in &self.power_ups {
+            roll -= spec.weight.max(0.0);
+            if roll < 0.0 {
+                return Some(spec);
+            }
+        }
+        self.power_ups.last()
+    }
+
+    fn duration(&self, kind: PowerUpKind) -> f32 {
+        self.power_ups
+            .iter()
+            .find(|x| x.kind == kind)
+            .map_or(0.0, |x| x.duration)
+    }
+}
+
+/// One kind of power-up that can appear.
+#[derive(Reflect, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowerUpSpec {
+    pub kind: PowerUpKind,
+    /// How likely this is to appear, relative to the other power-ups.
+    pub weight: f32,
+    /// How long the effect lasts in seconds.
+    pub duration: f32,
+}
+
+impl PowerUpSpec {
+    fn new(kind: PowerUpKind, weight: f32, duration: f32) -> Self {
+        Self {
+            kind,
+            weight,
+            duration,
+        }
+    }
+}
+
+/// A timed effect for the side that last hit the ball before it touched a pickup.
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    /// The side's paddle gets taller.
+    BigPaddle,
+    /// The ball moves faster toward the other side.
+    FastBall,
+    /// The other side's controls are reversed.
+    ReversedControls,
+    /// A wall blocks part of the side's goal.
+    ExtraWall,
+}
+
+impl PowerUpKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::BigPaddle => "Big paddle",
+            Self::FastBall => "Fast ball",
+            Self::ReversedControls => "Reversed",
+            Self::ExtraWall => "Extra wall",
+        }
+    }
+
+    fn color(self) -> Srgba {
+        match self {
+            Self::BigPaddle => Srgba::rgb(0.4, 0.8, 0.4),
+            Self::FastBall => Srgba::rgb(0.9, 0.6, 0.2),
+            Self::ReversedControls => Srgba::rgb(0.7, 0.4, 0.9),
+            Self::ExtraWall => Srgba::rgb(0.4, 0.6, 0.9),
+        }
+    }
+}
+
+/// A power-up waiting in the arena to be collected.
+#[derive(Reflect, Copy, Clone, PartialEq, Debug)]
+pub struct Pickup {
+    pub kind: PowerUpKind,
+    pub position: Vec2,
+}
+
+/// A power-up effect that's currently running.
+#[derive(Reflect, Clone, Debug)]
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    /// The side that collected the power-up.
+    pub side: Side,
+    pub timer: Timer,
+}
+
+/// The pickups in the arena and the effects that are running.
+///
+/// Pickups are collected by checking distances instead of with physics, so this resource
+/// is all that's needed to roll back or replay a match with power-ups.
+#[derive(Resource, Reflect, Clone, Default)]
+#[reflect(Resource)]
+pub struct PowerUps {
+    pub pickups: Vec<Pickup>,
+    pub active: Vec<ActivePowerUp>,
+    spawn_timer: Timer,
+}
+
+impl Configure for PowerUps {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            state!(Screen::Gameplay | Screen::Replay).on_enter(reset_power_ups),
+        );
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update(
+                (
+                    (tick_power_ups, spawn_pickups)
+                        .chain()
+                        .in_set(UpdateSystems::TickTimers),
+                    (collect_pickups, apply_big_paddles).in_set(UpdateSystems::Update),
+                )
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+    }
+}
+
+impl PowerUps {
+    /// Whether an effect is running for the given side.
+    pub fn is_active(&self, kind: PowerUpKind, side: Side) -> bool {
+        self.active.iter().any(|x| x.kind == kind && x.side == side)
+    }
+
+    /// Whether the given side's controls are reversed.
+    pub fn is_reversed(&self, side: Side) -> bool {
+        self.is_active(PowerUpKind::ReversedControls, side.opponent())
+    }
+
+    /// How much faster than normal a ball with the given velocity should move.
+    ///
+    /// A fast ball speeds up while it's moving away from the side that collected it.
+    pub fn ball_speed_scale(&self, velocity: Vec2, config: &PowerUpConfig) -> f32 {
+        let is_fast = self.active.iter().any(|x| {
+            x.kind == PowerUpKind::FastBall && x.side.opponent().direction().dot(velocity) > 0.0
+        });
+        if is_fast { config.fast_ball_scale } else { 1.0 }
+    }
+
+    /// Start an effect for the given side, or restart it if it's already running.
+    fn activate(&mut self, kind: PowerUpKind, side: Side, duration: f32) {
+        let timer = Timer::from_seconds(duration, TimerMode::Once);
+        match self
+            .active
+            .iter_mut()
+            .find(|x| x.kind == kind && x.side == side)
+        {
+            Some(active) => active.timer = timer,
+            None => self.active.push(ActivePowerUp { kind, side, timer }),
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_power_ups(config: ConfigRef<PowerUpConfig>, mut power_ups: ResMut<PowerUps>) {
+    let config = r!(config.get());
+    *power_ups = PowerUps {
+        spawn_timer: Timer::from_seconds(config.spawn_interval, TimerMode::Repeating),
+        ..default()
+    };
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_power_ups(time: Res<Time>, mut power_ups: ResMut<PowerUps>) {
+    rq!(!power_ups.active.is_empty());
+    for active in &mut power_ups.active {
+        active.timer.tick(time.delta());
+    }
+    power_ups.active.retain(|x| !x.timer.finished());
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_pickups(
+    time: Res<Time>,
+    config: ConfigRef<PowerUpConfig>,
+    arena_config: ConfigRef<ArenaConfig>,
+    setup: Res<MatchSetup>,
+    serve: Res<Serve>,
+    mut rng: ResMut<GameRng>,
+    mut power_ups: ResMut<PowerUps>,
+) {
+    // Only count down while the ball is in play.
+    rq!(!serve.is_serving());
+    let config = r!(config.get());
+    let arena_config = r!(arena_config.get());
+    rq!(power_ups.spawn_timer.tick(time.delta()).just_finished());
+    rq!(power_ups.pickups.len() < config.max_pickups);
+    let kind = rq!(config.pick(&mut *rng)).kind;
+
+    // Keep pickups between the paddles and away from the walls.
+    let inside_paddle = |side| {
+        arena_config.paddle_position(side, Slot::Back).length()
+            - arena_config.paddle_size.x
+            - config.pickup_radius
+    };
+    let max_x = inside_paddle(Side::Right).max(0.0);
+    let max_y = if setup.is_free_for_all() {
+        inside_paddle(Side::Top)
+    } else {
+        arena_config.half_height() - config.pickup_radius
+    }
+    .max(0.0);
+    let position = vec2(rng.gen_range(-max_x..=max_x), rng.gen_range(-max_y..=max_y));
+    power_ups.pickups.push(Pickup { kind, position });
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn collect_pickups(
+    config: ConfigRef<PowerUpConfig>,
+    rally: Res<Rally>,
+    mut power_ups: ResMut<PowerUps>,
+    ball_query: Query<(&Transform, &Collider), With<Ball>>,
+) {
+    rq!(!power_ups.pickups.is_empty());
+    // Nobody can collect a pickup until someone has hit the ball.
+    let side = rq!(rally.last_hit);
+    let config = r!(config.get());
+
+    for (transform, collider) in &ball_query {
+        let ball_pos = transform.translation.xy();
+        let reach = collider.shape().as_ball().map_or(0.0, |x| x.radius) + config.pickup_radius;
+        let mut collected = vec![];
+        power_ups.pickups.retain(|pickup| {
+            let touching = pickup.position.distance_squared(ball_pos) <= reach * reach;
+            if touching {
+                collected.push(pickup.kind);
+            }
+            !touching
+        });
+        for kind in collected {
+            power_ups.activate(kind, side, config.duration(kind));
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_big_paddles(
+    config: ConfigRef<PowerUpConfig>,
+    arena_config: ConfigRef<ArenaConfig>,
+    power_ups: Res<PowerUps>,
+    mut paddle_query: Query<(&Side, &mut Collider, &mut Sprite), With<Player>>,
+) {
+    let config = r!(config.get());
+    let arena_config = r!(arena_config.get());
+
+    for (&side, mut collider, mut sprite) in &mut paddle_query {
+        let base_size = arena_config.paddle_size(side);
+        let size = if !power_ups.is_active(PowerUpKind::BigPaddle, side) {
+            base_size
+        } else if side.is_horizontal() {
+            vec2(base_size.x * config.big_paddle_scale, base_size.y)
+        } else {
+            vec2(base_size.x, base_size.y * config.big_paddle_scale)
+        };
+        // Avoid rebuilding the collider every tick.
+        cq!(sprite.custom_size != Some(size));
+        *collider = Collider::rectangle(size.x, size.y);
+        sprite.custom_size = Some(size);
+    }
+}
+
+/// The sprite for a [`Pickup`] in the arena.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct PickupSprite(Pickup);
+
+impl Configure for PickupSprite {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_update(sync_pickup_sprites.in_set(UpdateSystems::SyncLate)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn sync_pickup_sprites(
+    mut commands: Commands,
+    config: ConfigRef<PowerUpConfig>,
+    power_ups: Res<PowerUps>,
+    sprite_query: Query<(Entity, &PickupSprite)>,
+) {
+    // Pickups can reappear after a rollback, so rebuild the sprites whenever they differ.
+    rq!(sprite_query.iter().count() != power_ups.pickups.len()
+        || power_ups
+            .pickups
+            .iter()
+            .any(|pickup| !sprite_query.iter().any(|(_, sprite)| sprite.0 == *pickup)));
+    let config = r!(config.get());
+
+    for (entity, _) in &sprite_query {
+        commands.entity(entity).despawn();
+    }
+    for &pickup in &power_ups.pickups {
+        commands.spawn((
+            Name::new(format!("Pickup{:?}", pickup.kind)),
+            PickupSprite(pickup),
+            Sprite::from_color(pickup.kind.color(), Vec2::splat(2.0 * config.pickup_radius)),
+            Transform::from_translation(pickup.position.extend(-1.0)),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+}
+
+/// A temporary wall in front of a goal, from [`PowerUpKind::ExtraWall`].
+#[derive(Component, Reflect, Copy, Clone, Debug)]
+#[reflect(Component)]
+pub struct PowerUpWall(pub Side);
+
+impl Configure for PowerUpWall {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update(
+                sync_power_up_walls
+                    .in_set(UpdateSystems::Update)
+                    .after(collect_pickups),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn sync_power_up_walls(
+    mut commands: Commands,
+    config: ConfigRef<PowerUpConfig>,
+    arena_config: ConfigRef<ArenaConfig>,
+    power_ups: Res<PowerUps>,
+    wall_query: Query<(Entity, &PowerUpWall)>,
+) {
+    let config = r!(config.get());
+    let arena_config = r!(arena_config.get());
+
+    for (entity, wall) in &wall_query {
+        if !power_ups.is_active(PowerUpKind::ExtraWall, wall.0) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for side in Side::ALL {
+        cq!(power_ups.is_active(PowerUpKind::ExtraWall, side));
+        cq!(!wall_query.iter().any(|(_, wall)| wall.0 == side));
+
+        // Stand halfway between the paddle and the goal.
+        let direction = side.direction();
+        let goal_distance = (arena_config.arena_size / 2.0).dot(direction.abs());
+        let distance =
+            (arena_config.paddle_position(side, Slot::Back).length() + goal_distance) / 2.0;
+        let position = direction * distance;
+        let size = vec2(arena_config.boundary_width, config.extra_wall_length);
+        let size = if side.is_horizontal() {
+            size.yx()
+        } else {
+            size
+        };
+        commands.spawn((
+            Name::new(format!("PowerUpWall{side:?}")),
+            PowerUpWall(side),
+            RigidBody::Static,
+            Collider::rectangle(size.x, size.y),
+            Transform::from_translation(position.extend(0.0)),
+            Sprite::from_color(PowerUpKind::ExtraWall.color(), size),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_up_effects() {
+        let config = PowerUpConfig::default();
+        let mut power_ups = PowerUps::default();
+        power_ups.activate(PowerUpKind::FastBall, Side::Left, 5.0);
+        power_ups.activate(PowerUpKind::ReversedControls, Side::Left, 5.0);
+
+        // Fast ball only speeds up the ball toward the other side.
+        assert_eq!(
+            power_ups.ball_speed_scale(vec2(100.0, 50.0), &config),
+            config.fast_ball_scale
+        );
+        assert_eq!(power_ups.ball_speed_scale(vec2(-100.0, 50.0), &config), 1.0);
+
+        // Reversed controls affect the other side.
+        assert!(power_ups.is_reversed(Side::Right));
+        assert!(!power_ups.is_reversed(Side::Left));
+
+        // Collecting the same power-up again restarts it instead of stacking.
+        power_ups.active[0].timer.tick(Duration::from_secs(3));
+        power_ups.activate(PowerUpKind::FastBall, Side::Left, 5.0);
+        assert_eq!(power_ups.active.len(), 2);
+        assert_eq!(power_ups.active[0].timer.elapsed_secs(), 0.0);
+    }
+}
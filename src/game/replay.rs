@@ -0,0 +1,441 @@
+use bevy::app::FixedMain;
+use bevy::ecs::system::SystemParam;
+
+use crate::core::rng::GameRng;
+use crate::game::Player;
+use crate::game::Side;
+use crate::game::Slot;
+use crate::game::arena::ArenaConfig;
+use crate::game::ball::BallConfig;
+use crate::game::input::PaddleInput;
+use crate::game::input::move_paddles;
+use crate::game::layout::LayoutConfig;
+use crate::game::mode::MatchSetup;
+use crate::game::mode::seed_game_rng;
+use crate::game::powerup::PowerUpConfig;
+use crate::game::reset_score;
+use crate::game::rules::MatchConfig;
+use crate::game::rules::reset_lives;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::util::config_local_dir;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ReplayRecorder, LastReplay, ReplayPlayback)>();
+}
+
+/// A recorded match, with everything needed to simulate it again exactly.
+#[derive(Reflect, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Replay {
+    pub setup: MatchSetup,
+    pub seed: u64,
+    pub configs: ReplayConfigs,
+    /// The paddle inputs on each tick, run-length encoded.
+    pub inputs: Vec<InputRun>,
+}
+
+/// The same paddle inputs repeated for a number of ticks.
+#[derive(Reflect, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InputRun {
+    pub ticks: u32,
+    /// The [`PaddleInput`] for each paddle, in [`MatchSetup::paddles`] order.
+    pub inputs: Vec<i8>,
+}
+
+/// The tuning that affects how a match plays out.
+///
+/// It's saved with each replay, so the replay plays back the same way after the config
+/// files change.
+#[derive(Reflect, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayConfigs {
+    pub arena: ArenaConfig,
+    pub ball: BallConfig,
+    pub rules: MatchConfig,
+    pub power_ups: PowerUpConfig,
+    pub layout: LayoutConfig,
+}
+
+#[derive(SystemParam)]
+struct ReplayConfigsRef<'w> {
+    arena: ConfigRef<'w, ArenaConfig>,
+    ball: ConfigRef<'w, BallConfig>,
+    rules: ConfigRef<'w, MatchConfig>,
+    power_ups: ConfigRef<'w, PowerUpConfig>,
+    layout: ConfigRef<'w, LayoutConfig>,
+}
+
+impl ReplayConfigsRef<'_> {
+    fn get(&self) -> Option<ReplayConfigs> {
+        Some(ReplayConfigs {
+            arena: self.arena.get()?.clone(),
+            ball: self.ball.get()?.clone(),
+            rules: self.rules.get()?.clone(),
+            power_ups: self.power_ups.get()?.clone(),
+            layout: self.layout.get()?.clone(),
+        })
+    }
+}
+
+#[derive(SystemParam)]
+struct ReplayConfigsMut<'w> {
+    arena: ConfigMut<'w, ArenaConfig>,
+    ball: ConfigMut<'w, BallConfig>,
+    rules: ConfigMut<'w, MatchConfig>,
+    power_ups: ConfigMut<'w, PowerUpConfig>,
+    layout: ConfigMut<'w, LayoutConfig>,
+}
+
+impl ReplayConfigsMut<'_> {
+    /// Swap in the given configs, returning the previous ones.
+    fn replace(&mut self, configs: ReplayConfigs) -> Option<ReplayConfigs> {
+        Some(ReplayConfigs {
+            arena: std::mem::replace(self.arena.get_mut()?, configs.arena),
+            ball: std::mem::replace(self.ball.get_mut()?, configs.ball),
+            rules: std::mem::replace(self.rules.get_mut()?, configs.rules),
+            power_ups: std::mem::replace(self.power_ups.get_mut()?, configs.power_ups),
+            layout: std::mem::replace(self.layout.get_mut()?, configs.layout),
+        })
+    }
+}
+
+impl Replay {
+    pub fn new(setup: MatchSetup, seed: u64, configs: ReplayConfigs) -> Self {
+        Self {
+            setup,
+            seed,
+            configs,
+            inputs: vec![],
+        }
+    }
+
+    /// The length of the replay in ticks.
+    pub fn tick_count(&self) -> u32 {
+        self.inputs.iter().map(|run| run.ticks).sum()
+    }
+
+    /// Record the inputs for the next tick.
+    pub fn push(&mut self, inputs: Vec<i8>) {
+        match self.inputs.last_mut() {
+            Some(run) if run.inputs == inputs => run.ticks += 1,
+            _ => self.inputs.push(InputRun { ticks: 1, inputs }),
+        }
+    }
+
+    /// Forget the inputs from the given tick onward.
+    pub fn truncate(&mut self, ticks: u32) {
+        let mut remaining = ticks;
+        self.inputs.retain_mut(|run| {
+            run.ticks = run.ticks.min(remaining);
+            remaining -= run.ticks;
+            run.ticks > 0
+        });
+    }
+
+    /// The inputs for every tick, in order.
+    pub fn expand(&self) -> Vec<Vec<i8>> {
+        self.inputs
+            .iter()
+            .flat_map(|run| std::iter::repeat_n(&run.inputs, run.ticks as usize).cloned())
+            .collect()
+    }
+
+    /// Write this replay to a new file in the replays folder.
+    pub fn save(&self) -> Option<std::path::PathBuf> {
+        let dir = replay_dir()?;
+        std::fs::create_dir_all(&dir).ok()?;
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        let path = dir.join(format!("replay-{secs}.ron"));
+        std::fs::write(&path, ron::to_string(self).ok()?).ok()?;
+        Some(path)
+    }
+
+    /// Read the most recently saved replay from the replays folder.
+    pub fn load_latest() -> Option<Self> {
+        let path = std::fs::read_dir(replay_dir()?)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|x| x == "ron"))
+            .max_by_key(|entry| entry.metadata().and_then(|x| x.modified()).ok())?
+            .path();
+        ron::from_str(&std::fs::read_to_string(path).ok()?).ok()
+    }
+}
+
+/// The folder for replay files, next to the settings file.
+fn replay_dir() -> Option<std::path::PathBuf> {
+    Some(config_local_dir()?.join("replays"))
+}
+
+/// Records the current match.
+#[derive(Resource, Default)]
+pub struct ReplayRecorder(pub Option<Replay>);
+
+impl Configure for ReplayRecorder {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            Screen::Gameplay.on_edge(finish_recording, start_recording.after(seed_game_rng)),
+        );
+        app.add_systems(
+            FixedUpdate,
+            Screen::Gameplay.on_update(
+                record_replay_tick
+                    .in_set(UpdateSystems::HandleEvents)
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    setup: Res<MatchSetup>,
+    rng: Res<GameRng>,
+    configs: ReplayConfigsRef,
+) {
+    let configs = r!(configs.get());
+    recorder.0 = Some(Replay::new(*setup, rng.seed(), configs));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_replay_tick(
+    mut recorder: ResMut<ReplayRecorder>,
+    setup: Res<MatchSetup>,
+    paddle_query: Query<(&Side, &Slot, &PaddleInput), With<Player>>,
+) {
+    let replay = rq!(recorder.0.as_mut());
+    let mut inputs = vec![0; setup.paddles().len()];
+    for (&side, &slot, input) in &paddle_query {
+        inputs[cq!(setup.player_index(side, slot))] = input.0;
+    }
+    replay.push(inputs);
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn finish_recording(mut recorder: ResMut<ReplayRecorder>, mut last_replay: ResMut<LastReplay>) {
+    let replay = rq!(recorder.0.take());
+    rq!(replay.tick_count() > 0);
+
+    #[cfg(feature = "native")]
+    match replay.save() {
+        Some(path) => info!("Saved replay: {}", path.display()),
+        None => warn!("Failed to save replay"),
+    }
+    last_replay.0 = Some(replay);
+}
+
+/// The most recently finished match.
+#[derive(Resource, Default)]
+pub struct LastReplay(pub Option<Replay>);
+
+impl Configure for LastReplay {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+    }
+}
+
+/// Plays back a [`Replay`] in [`Screen::Replay`].
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    inputs: Vec<Vec<i8>>,
+    /// The number of ticks played back so far.
+    pub tick: u32,
+    /// The tick to jump to, playing every tick in between instantly.
+    pub seek_to: Option<u32>,
+    pub paused: bool,
+    pub speed: f32,
+    /// The live match setup and configs, to restore after playback.
+    live_setup: Option<MatchSetup>,
+    live_configs: Option<ReplayConfigs>,
+}
+
+impl Configure for ReplayPlayback {
+    fn configure(app: &mut App) {
+        app.add_systems(
+            StateFlush,
+            Screen::Replay.on_edge(
+                end_playback,
+                begin_playback
+                    .before(reset_score)
+                    .before(reset_lives)
+                    .before(seed_game_rng),
+            ),
+        );
+        app.add_systems(
+            FixedUpdate,
+            Screen::Replay.on_update(
+                play_back_paddle_input
+                    .in_set(UpdateSystems::RecordInput)
+                    .before(move_paddles)
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+        app.add_systems(
+            Update,
+            Screen::Replay.on_update((
+                seek_replay.in_set(UpdateSystems::Update),
+                sync_replay_time.in_set(UpdateSystems::SyncLate),
+            )),
+        );
+    }
+}
+
+impl ReplayPlayback {
+    pub const SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            inputs: replay.expand(),
+            replay,
+            tick: 0,
+            seek_to: None,
+            paused: false,
+            speed: 1.0,
+            live_setup: None,
+            live_configs: None,
+        }
+    }
+
+    /// The length of the replay in ticks.
+    pub fn tick_count(&self) -> u32 {
+        self.inputs.len() as u32
+    }
+
+    /// Whether playback has reached the end of the replay.
+    pub fn is_finished(&self) -> bool {
+        self.tick >= self.tick_count()
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn begin_playback(
+    mut playback: ResMut<ReplayPlayback>,
+    mut setup: ResMut<MatchSetup>,
+    mut configs: ReplayConfigsMut,
+) {
+    let replay_setup = MatchSetup {
+        seed: Some(playback.replay.seed),
+        ..playback.replay.setup
+    };
+    let live_configs = r!(configs.replace(playback.replay.configs.clone()));
+
+    playback.tick = 0;
+    playback.live_setup = Some(std::mem::replace(&mut *setup, replay_setup));
+    playback.live_configs = Some(live_configs);
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn end_playback(
+    mut playback: ResMut<ReplayPlayback>,
+    mut setup: ResMut<MatchSetup>,
+    mut configs: ReplayConfigsMut,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if let Some(live_setup) = playback.live_setup.take() {
+        *setup = live_setup;
+    }
+    if let Some(live_configs) = playback.live_configs.take() {
+        r!(configs.replace(live_configs));
+    }
+    time.unpause();
+    time.set_relative_speed(1.0);
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn play_back_paddle_input(
+    mut playback: ResMut<ReplayPlayback>,
+    setup: Res<MatchSetup>,
+    mut paddle_query: Query<(&Side, &Slot, &mut PaddleInput), With<Player>>,
+) {
+    let inputs = playback.inputs.get(playback.tick as usize);
+    for (&side, &slot, mut input) in &mut paddle_query {
+        let player = cq!(setup.player_index(side, slot));
+        let value = inputs
+            .and_then(|x| x.get(player))
+            .copied()
+            .unwrap_or_default();
+        input.set_if_neq(PaddleInput(value));
+    }
+
+    playback.tick += 1;
+    if playback.tick == playback.tick_count() {
+        playback.paused = true;
+    }
+}
+
+/// Play every tick up to [`ReplayPlayback::seek_to`] at once.
+fn seek_replay(world: &mut World) {
+    let playback = r!(world.get_resource::<ReplayPlayback>());
+    let target = rq!(playback.seek_to).min(playback.tick_count());
+    // Seeking backwards restarts the replay first.
+    rq!(playback.tick <= target);
+    let ticks = target - playback.tick;
+
+    // Run the fixed timestep schedule directly, as if that much time had passed.
+    let time = *r!(world.get_resource::<Time>());
+    for _ in 0..ticks {
+        let fixed_time = r!(world.get_resource::<Time<Fixed>>()).as_generic();
+        *r!(world.get_resource_mut::<Time>()) = fixed_time;
+        world.run_schedule(FixedMain);
+    }
+    *r!(world.get_resource_mut::<Time>()) = time;
+
+    r!(world.get_resource_mut::<ReplayPlayback>()).seek_to = None;
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn sync_replay_time(playback: Res<ReplayPlayback>, mut time: ResMut<Time<Virtual>>) {
+    if playback.paused {
+        time.pause();
+    } else {
+        time.unpause();
+    }
+    time.set_relative_speed(playback.speed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::mode::PlayMode;
+    use crate::headless::HeadlessMatch;
+
+    #[test]
+    fn test_run_length_encoding() {
+        let mut replay = Replay::new(default(), 0, default());
+        for inputs in [[0, 0], [0, 0], [127, 0], [127, 0], [127, 0], [0, -127]] {
+            replay.push(inputs.to_vec());
+        }
+
+        assert_eq!(replay.inputs.len(), 3);
+        assert_eq!(replay.tick_count(), 6);
+        assert_eq!(replay.expand()[3], [127, 0]);
+        assert_eq!(replay.expand()[5], [0, -127]);
+
+        replay.truncate(3);
+        assert_eq!(replay.inputs.len(), 2);
+        assert_eq!(replay.tick_count(), 3);
+    }
+
+    #[test]
+    fn test_records_every_paddle() {
+        let mut game = HeadlessMatch::new(MatchSetup {
+            mode: PlayMode::Doubles,
+            ..default()
+        })
+        .start();
+        game.step(10);
+
+        let recorder = game.app.world().resource::<ReplayRecorder>();
+        let replay = recorder.0.as_ref().unwrap();
+        assert!(replay.tick_count() >= 10);
+        assert!(replay.expand().iter().all(|inputs| inputs.len() == 4));
+    }
+}
@@ -0,0 +1,356 @@
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::event::MatchWon;
+use crate::game::mode::MatchSetup;
+use crate::game::netplay::NetSession;
+use crate::game::serve::ServeRule;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::fade::fade_out;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<MatchConfig>, MatchClock, Lives, MatchResult)>();
+}
+
+/// The rules for winning a match and serving the ball, and how goals are replayed.
+#[derive(Asset, Reflect, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct MatchConfig {
+    /// The number of points required to win.
+    pub points_to_win: u32,
+    /// Whether the winner must lead by at least two points.
+    pub win_by_two: bool,
+    /// The match length in seconds, after which the leading side wins.
+    /// A tied score at the time limit is decided by the next point.
+    pub time_limit: Option<f32>,
+    /// The lives each player starts with in free-for-all matches.
+    pub lives: u32,
+
+    /// Which side to serve toward after a goal.
+    pub serve_rule: ServeRule,
+    /// The countdown in seconds before each serve.
+    pub serve_countdown: f32,
+    /// The minimum serve angle in degrees away from horizontal.
+    pub serve_min_angle: f32,
+    /// The maximum serve angle in degrees away from horizontal.
+    pub serve_max_angle: f32,
+
+    /// How many seconds before each goal to replay, or zero to skip goal replays.
+    pub goal_replay_secs: f32,
+    /// The playback speed of goal replays.
+    pub goal_replay_speed: f32,
+}
+
+impl Default for MatchConfig {
+    fn default() -> Self {
+        Self {
+            points_to_win: 11,
+            win_by_two: true,
+            time_limit: None,
+            lives: 3,
+
+            serve_rule: ServeRule::ToConceder,
+            serve_countdown: 3.0,
+            serve_min_angle: 15.0,
+            serve_max_angle: 45.0,
+
+            goal_replay_secs: 3.0,
+            goal_replay_speed: 0.4,
+        }
+    }
+}
+
+impl Config for MatchConfig {
+    const FILE: &'static str = "match.ron";
+}
+
+impl MatchConfig {
+    /// The winning side for a given score and elapsed match time, if the match is over.
+    pub fn winner(&self, score: &Score, elapsed: f32) -> Option<Side> {
+        let leader = score.leader()?;
+        let required_lead = if self.win_by_two { 2 } else { 1 };
+        if score.get(leader) >= self.points_to_win && score.lead() >= required_lead {
+            return Some(leader);
+        }
+
+        self.time_limit
+            .is_some_and(|limit| elapsed >= limit)
+            .then_some(leader)
+    }
+
+    /// The time left in the match, if there is a time limit.
+    pub fn time_remaining(&self, elapsed: f32) -> Option<f32> {
+        self.time_limit.map(|limit| (limit - elapsed).max(0.0))
+    }
+}
+
+/// The total unpaused time elapsed in the current match.
+#[derive(Resource, Reflect, Clone, Default)]
+#[reflect(Resource)]
+pub struct MatchClock(pub Duration);
+
+impl Configure for MatchClock {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            state!(Screen::Gameplay | Screen::Replay).on_enter(reset_match_clock),
+        );
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update(
+                tick_match_clock
+                    .in_set(UpdateSystems::TickTimers)
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_match_clock(mut clock: ResMut<MatchClock>) {
+    *clock = default();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_match_clock(time: Res<Time>, mut clock: ResMut<MatchClock>) {
+    clock.0 += time.delta();
+}
+
+/// The lives left for each side in a free-for-all match, in [`Side::ALL`] order.
+///
+/// A side with no lives left is out of the match.
+#[derive(Resource, Reflect, Clone, Default, Debug)]
+#[reflect(Resource)]
+pub struct Lives(pub [u32; 4]);
+
+impl Configure for Lives {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            state!(Screen::Gameplay | Screen::Replay).on_enter(reset_lives),
+        );
+    }
+}
+
+impl Lives {
+    /// The order that play passes around the arena.
+    const CLOCKWISE: [Side; 4] = [Side::Left, Side::Top, Side::Right, Side::Bottom];
+
+    pub fn get(&self, side: Side) -> u32 {
+        self.0[side.index()]
+    }
+
+    pub fn is_alive(&self, side: Side) -> bool {
+        self.get(side) > 0
+    }
+
+    /// Take a life from the given side, and return whether that eliminated it.
+    pub fn lose(&mut self, side: Side) -> bool {
+        let lives = &mut self.0[side.index()];
+        let was_alive = *lives > 0;
+        *lives = lives.saturating_sub(1);
+        was_alive && *lives == 0
+    }
+
+    /// The first side still in the match, going clockwise from the given side.
+    pub fn next_alive(&self, side: Side) -> Option<Side> {
+        let start = Self::CLOCKWISE.iter().position(|&x| x == side)?;
+        (0..4)
+            .map(|i| Self::CLOCKWISE[(start + i) % 4])
+            .find(|&x| self.is_alive(x))
+    }
+
+    /// The winning side, if only one is left or the time limit has passed with a clear leader.
+    pub fn winner(&self, config: &MatchConfig, elapsed: f32) -> Option<Side> {
+        let mut alive = Side::ALL.into_iter().filter(|&x| self.is_alive(x));
+        if let (Some(last), None) = (alive.next(), alive.next()) {
+            return Some(last);
+        }
+        if !config.time_limit.is_some_and(|limit| elapsed >= limit) {
+            return None;
+        }
+
+        let most = Side::ALL.map(|x| self.get(x)).into_iter().max()?;
+        let mut leaders = Side::ALL.into_iter().filter(|&x| self.get(x) == most);
+        match (leaders.next(), leaders.next()) {
+            (Some(leader), None) => Some(leader),
+            _ => None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+pub fn reset_lives(
+    config: ConfigRef<MatchConfig>,
+    setup: Res<MatchSetup>,
+    mut lives: ResMut<Lives>,
+) {
+    let config = r!(config.get());
+    *lives = default();
+    for &side in setup.sides() {
+        lives.0[side.index()] = config.lives.max(1);
+    }
+}
+
+/// The outcome of the most recently finished match.
+#[derive(Resource, Reflect, Copy, Clone, Debug)]
+#[reflect(Resource)]
+pub struct MatchResult {
+    pub winner: Side,
+    pub score: Score,
+    pub duration: Duration,
+}
+
+impl Configure for MatchResult {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(StateFlush, Screen::Gameplay.on_enter(clear_match_result));
+        app.add_systems(
+            FixedUpdate,
+            Screen::Gameplay.on_update(
+                check_match_end
+                    .in_set(UpdateSystems::Update)
+                    .run_if(Pause::is_disabled.and(not(resource_exists::<MatchResult>))),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn clear_match_result(mut commands: Commands) {
+    commands.remove_resource::<MatchResult>();
+}
+
+/// The side that has won the match so far, if any.
+fn match_winner(
+    config: &MatchConfig,
+    setup: &MatchSetup,
+    score: &Score,
+    lives: &Lives,
+    clock: &MatchClock,
+) -> Option<Side> {
+    let elapsed = clock.0.as_secs_f32();
+    if setup.is_free_for_all() {
+        lives.winner(config, elapsed)
+    } else {
+        config.winner(score, elapsed)
+    }
+}
+
+/// Whether a side has won the match, including with a goal scored this tick.
+pub fn is_match_decided(
+    config: ConfigRef<MatchConfig>,
+    setup: Res<MatchSetup>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    clock: Res<MatchClock>,
+) -> bool {
+    config
+        .get()
+        .is_some_and(|config| match_winner(config, &setup, &score, &lives, &clock).is_some())
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn check_match_end(
+    mut commands: Commands,
+    config: ConfigRef<MatchConfig>,
+    setup: Res<MatchSetup>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    clock: Res<MatchClock>,
+    session: Option<Res<NetSession>>,
+    mut match_events: EventWriter<MatchWon>,
+) {
+    let config = r!(config.get());
+    let winner = rq!(match_winner(config, &setup, &score, &lives, &clock));
+
+    commands.insert_resource(MatchResult {
+        winner,
+        score: *score,
+        duration: clock.0,
+    });
+    match_events.write(MatchWon {
+        winner,
+        score: *score,
+    });
+    // A LAN match could still roll back past this tick, so it ends once the tick is confirmed.
+    if session.is_none() {
+        commands.spawn(fade_out(Screen::Results));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(player1: u32, player2: u32) -> Score {
+        Score { player1, player2 }
+    }
+
+    #[test]
+    fn test_first_to_n() {
+        let config = MatchConfig {
+            points_to_win: 5,
+            win_by_two: false,
+            time_limit: None,
+            ..default()
+        };
+        assert_eq!(config.winner(&score(4, 4), 0.0), None);
+        assert_eq!(config.winner(&score(5, 4), 0.0), Some(Side::Left));
+        assert_eq!(config.winner(&score(3, 5), 0.0), Some(Side::Right));
+    }
+
+    #[test]
+    fn test_win_by_two() {
+        let config = MatchConfig {
+            points_to_win: 5,
+            win_by_two: true,
+            time_limit: None,
+            ..default()
+        };
+        assert_eq!(config.winner(&score(5, 4), 0.0), None);
+        assert_eq!(config.winner(&score(6, 4), 0.0), Some(Side::Left));
+        assert_eq!(config.winner(&score(9, 11), 0.0), Some(Side::Right));
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let config = MatchConfig {
+            points_to_win: 11,
+            win_by_two: true,
+            time_limit: Some(60.0),
+            ..default()
+        };
+        assert_eq!(config.winner(&score(2, 1), 59.0), None);
+        assert_eq!(config.winner(&score(2, 1), 60.0), Some(Side::Left));
+        // A tie at the time limit goes to sudden death.
+        assert_eq!(config.winner(&score(2, 2), 90.0), None);
+        assert_eq!(config.winner(&score(2, 3), 90.0), Some(Side::Right));
+    }
+
+    #[test]
+    fn test_last_side_standing() {
+        let config = MatchConfig {
+            time_limit: Some(60.0),
+            ..default()
+        };
+        let mut lives = Lives([1, 2, 1, 0]);
+        assert_eq!(lives.winner(&config, 0.0), None);
+        assert_eq!(lives.next_alive(Side::Bottom), Some(Side::Left));
+
+        // Losing the last life eliminates a side, and play passes to the next one.
+        assert!(lives.lose(Side::Left));
+        assert_eq!(lives.next_alive(Side::Left), Some(Side::Top));
+        assert_eq!(lives.winner(&config, 0.0), None);
+
+        // The side with the most lives wins at the time limit.
+        assert_eq!(lives.winner(&config, 60.0), Some(Side::Right));
+
+        assert!(lives.lose(Side::Top));
+        assert_eq!(lives.winner(&config, 0.0), Some(Side::Right));
+    }
+}
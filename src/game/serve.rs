@@ -0,0 +1,181 @@
+use avian2d::math::Vector;
+
+use crate::core::rng::GameRng;
+use crate::game::Ball;
+use crate::game::Side;
+use crate::game::ball::BallConfig;
+use crate::game::event::ServeLaunched;
+use crate::game::mode::MatchSetup;
+use crate::game::mode::seed_game_rng;
+use crate::game::rules::Lives;
+use crate::game::rules::MatchConfig;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(Serve, IsServeCountdown)>();
+}
+
+/// Which side the ball is served toward after a goal.
+#[derive(Reflect, Copy, Clone, Default, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ServeRule {
+    /// Serve toward the side that conceded the goal.
+    #[default]
+    ToConceder,
+    /// Serve toward each side in turn.
+    Alternate,
+}
+
+/// The ball waits at the center of the arena until the countdown finishes.
+#[derive(Resource, Reflect, Clone, Default)]
+#[reflect(Resource)]
+pub struct Serve {
+    pub toward: Option<Side>,
+    pub countdown: Option<Timer>,
+}
+
+impl Configure for Serve {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_enter(begin_first_serve.after(seed_game_rng)),
+        );
+        app.add_systems(
+            FixedUpdate,
+            state!(Screen::Gameplay | Screen::Replay).on_update(
+                tick_serve
+                    .in_set(UpdateSystems::TickTimers)
+                    .run_if(Pause::is_disabled),
+            ),
+        );
+    }
+}
+
+impl Serve {
+    pub fn is_serving(&self) -> bool {
+        self.countdown.is_some()
+    }
+
+    /// Start the countdown for the serve after `conceded` let in a goal.
+    pub fn after_goal(&mut self, conceded: Side, config: &MatchConfig, lives: &Lives) {
+        let toward = match (config.serve_rule, self.toward) {
+            (ServeRule::Alternate, Some(last)) => last.opponent(),
+            _ => conceded,
+        };
+        // Skip sides that are out of the match.
+        let toward = lives.next_alive(toward).unwrap_or(toward);
+        self.begin(toward, config);
+    }
+
+    fn begin(&mut self, toward: Side, config: &MatchConfig) {
+        self.toward = Some(toward);
+        self.countdown = Some(Timer::from_seconds(config.serve_countdown, TimerMode::Once));
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn begin_first_serve(
+    config: ConfigRef<MatchConfig>,
+    setup: Res<MatchSetup>,
+    mut rng: ResMut<GameRng>,
+    mut serve: ResMut<Serve>,
+) {
+    let config = r!(config.get());
+    let sides = setup.sides();
+    let toward = if sides.len() == 2 {
+        if rng.r#gen() { Side::Left } else { Side::Right }
+    } else {
+        sides[rng.gen_range(0..sides.len())]
+    };
+    *serve = default();
+    serve.begin(toward, config);
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_serve(
+    time: Res<Time>,
+    config: ConfigRef<MatchConfig>,
+    ball_config: ConfigRef<BallConfig>,
+    mut rng: ResMut<GameRng>,
+    mut serve: ResMut<Serve>,
+    mut ball_query: Query<(Entity, &mut LinearVelocity), With<Ball>>,
+    mut serve_events: EventWriter<ServeLaunched>,
+) {
+    let config = r!(config.get());
+    let ball_config = r!(ball_config.get());
+    let toward = rq!(serve.toward);
+    let countdown = rq!(serve.countdown.as_mut());
+    rq!(countdown.tick(time.delta()).finished());
+
+    serve.countdown = None;
+    for (ball, mut velocity) in &mut ball_query {
+        velocity.0 = serve_direction(config, toward, &mut *rng) * ball_config.base_speed;
+        serve_events.write(ServeLaunched {
+            ball,
+            toward,
+            velocity: velocity.0,
+        });
+    }
+}
+
+/// A random serve direction toward the given side, within the configured angle range.
+pub(super) fn serve_direction(config: &MatchConfig, toward: Side, rng: &mut impl Rng) -> Vector {
+    let lo = config.serve_min_angle;
+    let hi = config.serve_max_angle.max(lo);
+    let angle = rng.gen_range(lo..=hi).to_radians();
+    let angle = if rng.r#gen() { angle } else { -angle };
+    let direction = toward.direction();
+    if toward.is_horizontal() {
+        Vector::new(angle.sin(), direction.y * angle.cos())
+    } else {
+        Vector::new(direction.x * angle.cos(), angle.sin())
+    }
+}
+
+/// The label that shows the serve countdown.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsServeCountdown;
+
+impl Configure for IsServeCountdown {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            StateFlush,
+            state!(Screen::Gameplay | Screen::Replay).on_enter(spawn_serve_countdown),
+        );
+        app.add_systems(
+            Update,
+            state!(Screen::Gameplay | Screen::Replay)
+                .on_update(update_serve_countdown.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_serve_countdown(mut commands: Commands, screen_root: Res<ScreenRoot>) {
+    commands.entity(screen_root.ui).with_child((
+        Name::new("ServeCountdown"),
+        Node::COLUMN_CENTER.full_size().abs(),
+        Pickable::IGNORE,
+        children![(widget::big_label(""), IsServeCountdown)],
+    ));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_serve_countdown(
+    serve: Res<Serve>,
+    mut label_query: Query<&mut RichText, With<IsServeCountdown>>,
+) {
+    let text = match &serve.countdown {
+        Some(countdown) => format!("[b]{}", countdown.remaining_secs().ceil().max(1.0)),
+        None => String::new(),
+    };
+    for mut label in &mut label_query {
+        label.sections = parse_rich(&text);
+    }
+}
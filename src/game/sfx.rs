@@ -0,0 +1,107 @@
+use bevy::audio::DefaultSpatialScale;
+use bevy::audio::SpatialScale;
+
+use crate::core::audio::AudioSettings;
+use crate::core::audio::IsSfxAudio;
+use crate::core::audio::SfxSystems;
+use crate::core::audio::VolumeScale;
+use crate::core::camera::CameraRoot;
+use crate::game::arena::ArenaConfig;
+use crate::game::ball::BallConfig;
+use crate::game::event::GoalScored;
+use crate::game::event::PaddleHit;
+use crate::game::event::ServeLaunched;
+use crate::game::event::WallHit;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::GameplayAssets;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        state!(Screen::Gameplay | Screen::Replay).on_enter(add_sfx_listener.in_set(SfxSystems)),
+    );
+    app.add_systems(
+        Update,
+        state!(Screen::Gameplay | Screen::Replay).on_update(
+            play_gameplay_sfx
+                .in_set(UpdateSystems::Update)
+                .in_set(SfxSystems),
+        ),
+    );
+}
+
+/// Hear the arena from the camera, with one ear at each goal.
+#[cfg_attr(feature = "native_dev", hot)]
+fn add_sfx_listener(
+    mut commands: Commands,
+    camera_root: Res<CameraRoot>,
+    config: ConfigRef<ArenaConfig>,
+) {
+    let half_width = r!(config.get()).arena_size.x / 2.0;
+    commands
+        .entity(camera_root.primary)
+        .insert(SpatialListener::new(2.0 * half_width));
+    // Scale the arena down so sounds fade a little with distance, but not too much.
+    commands.insert_resource(DefaultSpatialScale(SpatialScale::new_2d(1.0 / half_width)));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn play_gameplay_sfx(
+    mut commands: Commands,
+    assets: Res<GameplayAssets>,
+    audio_settings: Res<AudioSettings>,
+    arena_config: ConfigRef<ArenaConfig>,
+    ball_config: ConfigRef<BallConfig>,
+    mut paddle_events: EventReader<PaddleHit>,
+    mut wall_events: EventReader<WallHit>,
+    mut goal_events: EventReader<GoalScored>,
+    mut serve_events: EventReader<ServeLaunched>,
+) {
+    let arena_config = r!(arena_config.get());
+    let base_speed = r!(ball_config.get()).base_speed;
+    let half_width = arena_config.arena_size.x / 2.0;
+    let mut play = |handle: &Handle<AudioSource>, x: f32, speed: f32| {
+        commands.spawn((
+            sfx_audio(&audio_settings, handle.clone(), speed / base_speed),
+            Transform::from_xyz(x, 0.0, 0.0),
+        ));
+    };
+
+    // Play at most one of each sound per frame, in case many ticks ran at once.
+    if let Some(event) = paddle_events.read().last() {
+        play(&assets.paddle_hit, event.position.x, event.speed);
+    }
+    if let Some(event) = wall_events.read().last() {
+        play(&assets.wall_hit, event.position.x, event.speed);
+    }
+    if let Some(event) = goal_events.read().last() {
+        let x = event.conceded.direction().x * half_width;
+        play(&assets.goal, x, base_speed);
+    }
+    if let Some(event) = serve_events.read().last() {
+        play(&assets.serve, 0.0, event.velocity.length());
+    }
+}
+
+/// A one-shot sound effect, pitched up and louder the faster the ball is moving
+/// relative to its base speed. It's panned by its [`Transform`].
+fn sfx_audio(
+    audio_settings: &AudioSettings,
+    handle: Handle<AudioSource>,
+    intensity: f32,
+) -> impl Bundle {
+    let intensity = intensity.clamp(0.5, 2.5);
+    let scale = VolumeScale(intensity.sqrt());
+    (
+        Name::new("SfxAudio"),
+        AudioPlayer(handle),
+        PlaybackSettings::DESPAWN
+            .with_spatial(true)
+            .with_volume(scale.apply(audio_settings.sfx_volume()))
+            .with_speed(intensity.powf(0.3)),
+        IsSfxAudio,
+        scale,
+        DespawnOnExitState::<Screen>::Recursive,
+    )
+}
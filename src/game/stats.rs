@@ -0,0 +1,276 @@
+use bevy::ecs::relationship::RelatedSpawner;
+use bevy_simple_prefs::Prefs;
+use bevy_simple_prefs::PrefsPlugin;
+
+use crate::game::Ball;
+use crate::game::Side;
+use crate::game::ball::Rally;
+use crate::game::event::GoalScored;
+use crate::game::event::PaddleHit;
+use crate::game::event::ServeLaunched;
+use crate::game::mode::MatchSetup;
+use crate::game::rules::MatchResult;
+use crate::game::score_goal;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(MatchStats, LifetimeStats, StatsFile)>();
+}
+
+/// Statistics for the current match.
+#[derive(Resource, Reflect, Clone, Default, Debug)]
+#[reflect(Resource)]
+pub struct MatchStats {
+    /// The most paddle hits in a single rally.
+    pub longest_rally: u32,
+    /// The total paddle hits by each side, in [`Side::ALL`] order.
+    pub paddle_hits: [u32; 4],
+    /// The fastest the ball has moved, in world units per second.
+    pub top_ball_speed: f32,
+    /// The number of rallies that ended in a goal.
+    pub rallies: u32,
+    /// The total paddle hits in rallies that ended in a goal.
+    pub rally_hits: u32,
+    /// The points won by each side on rallies served toward them.
+    pub receiving_points: [u32; 4],
+    /// The points won by each side on rallies served toward another side.
+    pub serving_points: [u32; 4],
+    /// The side the current rally was served toward.
+    serve_toward: Option<Side>,
+}
+
+impl Configure for MatchStats {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            (
+                Screen::Gameplay.on_enter(reset_match_stats),
+                Screen::Results.on_enter(add_to_lifetime_stats),
+            ),
+        );
+        app.add_systems(
+            FixedUpdate,
+            Screen::Gameplay.on_update((
+                track_top_ball_speed
+                    .in_set(UpdateSystems::Update)
+                    .run_if(Pause::is_disabled),
+                track_rallies
+                    .in_set(UpdateSystems::HandleEvents)
+                    .before(score_goal),
+            )),
+        );
+    }
+}
+
+impl MatchStats {
+    /// The average number of paddle hits per rally.
+    pub fn average_rally(&self) -> f32 {
+        if self.rallies == 0 {
+            0.0
+        } else {
+            self.rally_hits as f32 / self.rallies as f32
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_match_stats(mut stats: ResMut<MatchStats>) {
+    *stats = default();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn track_top_ball_speed(
+    mut stats: ResMut<MatchStats>,
+    ball_query: Query<&LinearVelocity, With<Ball>>,
+) {
+    for velocity in &ball_query {
+        let speed = velocity.length();
+        // Avoid triggering change detection every tick.
+        if speed > stats.top_ball_speed {
+            stats.top_ball_speed = speed;
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn track_rallies(
+    mut serve_events: EventReader<ServeLaunched>,
+    mut paddle_events: EventReader<PaddleHit>,
+    mut goal_events: EventReader<GoalScored>,
+    rally: Res<Rally>,
+    mut stats: ResMut<MatchStats>,
+) {
+    for event in serve_events.read() {
+        stats.serve_toward = Some(event.toward);
+    }
+    for event in paddle_events.read() {
+        stats.paddle_hits[event.side.index()] += 1;
+    }
+    for event in goal_events.read() {
+        stats.rallies += 1;
+        stats.rally_hits += rally.hits;
+        stats.longest_rally = stats.longest_rally.max(rally.hits);
+        let i = event.scorer.index();
+        if stats.serve_toward == Some(event.scorer) {
+            stats.receiving_points[i] += 1;
+        } else {
+            stats.serving_points[i] += 1;
+        }
+    }
+}
+
+/// A panel that shows the stats for a match.
+pub fn stats_panel(stats: &MatchStats, setup: &MatchSetup, time_played: Duration) -> impl Bundle {
+    let sides = setup.sides();
+    let names = sides
+        .iter()
+        .map(|&side| setup.side_name(side))
+        .collect::<Vec<_>>();
+    let secs = time_played.as_secs();
+    let per_side = [
+        ("Paddle hits", stats.paddle_hits),
+        ("Points receiving serve", stats.receiving_points),
+        ("Points serving", stats.serving_points),
+    ]
+    .map(|(label, values)| (label, sides.iter().map(move |&side| values[side.index()])));
+    let overall = [
+        ("Longest rally", stats.longest_rally.to_string()),
+        ("Average rally", format!("{:.1}", stats.average_rally())),
+        ("Top ball speed", format!("{:.0}", stats.top_ball_speed)),
+        ("Time played", format!("{}:{:02}", secs / 60, secs % 60)),
+    ];
+
+    (
+        Name::new("StatsPanel"),
+        Node {
+            display: Display::Grid,
+            margin: UiRect::vertical(Vw(2.0)),
+            row_gap: Vw(0.6),
+            column_gap: Vw(3.0),
+            grid_template_columns: RepeatedGridTrack::auto(1 + sides.len() as u16),
+            ..default()
+        },
+        GridAlignment::columns(
+            std::iter::once(JustifySelf::End)
+                .chain(sides.iter().map(|_| JustifySelf::Center))
+                .collect::<Vec<_>>(),
+        ),
+        Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<ChildOf>| {
+            parent.spawn(widget::label(""));
+            for name in names {
+                parent.spawn(widget::label(format!("[b]{name}")));
+            }
+            for (label, values) in per_side {
+                parent.spawn(widget::label(label));
+                for value in values {
+                    parent.spawn(widget::label(value.to_string()));
+                }
+            }
+            for (label, value) in overall {
+                parent.spawn(widget::label(label));
+                parent.spawn(widget::label(value));
+                for _ in 1..sides.len() {
+                    parent.spawn(widget::label(""));
+                }
+            }
+        })),
+    )
+}
+
+/// Totals across every finished match, saved to `stats.ron`.
+#[derive(Resource, Reflect, Clone, Default, Debug)]
+#[reflect(Resource)]
+pub struct LifetimeStats {
+    pub matches_played: u32,
+    pub points_played: u32,
+    pub paddle_hits: u32,
+    pub rally_hits: u32,
+    pub longest_rally: u32,
+    pub top_ball_speed: f32,
+    pub time_played: Duration,
+}
+
+impl Configure for LifetimeStats {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl LifetimeStats {
+    fn add_match(&mut self, stats: &MatchStats, time_played: Duration) {
+        self.matches_played += 1;
+        self.points_played += stats.rallies;
+        self.paddle_hits += stats.paddle_hits.iter().sum::<u32>();
+        self.rally_hits += stats.rally_hits;
+        self.longest_rally = self.longest_rally.max(stats.longest_rally);
+        self.top_ball_speed = self.top_ball_speed.max(stats.top_ball_speed);
+        self.time_played += time_played;
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn add_to_lifetime_stats(
+    stats: Res<MatchStats>,
+    result: Res<MatchResult>,
+    mut lifetime: ResMut<LifetimeStats>,
+) {
+    lifetime.add_match(&stats, result.duration);
+}
+
+#[derive(Prefs, Reflect, Default)]
+struct StatsFile {
+    pub lifetime_stats: LifetimeStats,
+}
+
+impl Configure for StatsFile {
+    fn configure(app: &mut App) {
+        #[cfg(feature = "native")]
+        let path = r!(crate::util::config_local_dir());
+
+        app.add_plugins(PrefsPlugin::<StatsFile> {
+            filename: "stats.ron".to_string(),
+            #[cfg(feature = "native")]
+            path,
+            ..default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifetime_stats_add_match() {
+        let stats = MatchStats {
+            longest_rally: 7,
+            paddle_hits: [10, 12, 0, 0],
+            top_ball_speed: 900.0,
+            rallies: 5,
+            rally_hits: 20,
+            ..default()
+        };
+        let mut lifetime = LifetimeStats::default();
+        lifetime.add_match(&stats, Duration::from_secs(60));
+        lifetime.add_match(
+            &MatchStats {
+                longest_rally: 3,
+                top_ball_speed: 1200.0,
+                ..stats.clone()
+            },
+            Duration::from_secs(30),
+        );
+
+        assert_eq!(lifetime.matches_played, 2);
+        assert_eq!(lifetime.points_played, 10);
+        assert_eq!(lifetime.paddle_hits, 44);
+        assert_eq!(lifetime.longest_rally, 7);
+        assert_eq!(lifetime.top_ball_speed, 1200.0);
+        assert_eq!(lifetime.time_played, Duration::from_secs(90));
+        assert_eq!(stats.average_rally(), 4.0);
+    }
+}
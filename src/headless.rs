@@ -0,0 +1,284 @@
+//! Gameplay and physics without a window, rendering, or audio, for automated tests.
+//!
+//! It's always available to tests. Builds with the `headless` feature can also simulate a
+//! match from the command line with `--headless <ticks>`.
+
+use crate::core::input::ControlSlot;
+use crate::core::input::Controls;
+use crate::game::Ball;
+use crate::game::Score;
+use crate::game::Side;
+use crate::game::arena::ArenaConfig;
+use crate::game::ball::BallConfig;
+use crate::game::broadcast::BroadcastSystems;
+use crate::game::layout::LayoutConfig;
+use crate::game::mode::MatchSetup;
+use crate::game::powerup::PowerUpConfig;
+use crate::game::rules::MatchConfig;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+
+/// The headless counterpart to [`crate::plugin`], with only gameplay on top of the core.
+pub fn plugin(app: &mut App) {
+    app.add_plugins(crate::core::headless_plugin);
+    app.add_state::<Screen>();
+    app.init_resource::<ScreenRoot>();
+    app.add_plugins(crate::game::plugin);
+
+    // Don't let spectators watch every test match.
+    app.configure_sets(StateFlush, BroadcastSystems.run_if(|| false));
+}
+
+/// Simulate a match for the number of ticks after `--headless` and print how it went,
+/// if that argument was given.
+#[cfg(feature = "headless")]
+pub fn run_from_args() -> Option<AppExit> {
+    let mut args = std::env::args().skip_while(|x| x != "--headless");
+    args.next()?;
+    let Some(ticks) = args.next().and_then(|x| x.parse::<u32>().ok()) else {
+        eprintln!("Usage: --headless <ticks>");
+        return Some(AppExit::error());
+    };
+
+    let mut game = HeadlessMatch::new(default()).start();
+    game.step(ticks);
+    let score = game.score();
+    let (position, velocity) = game.ball();
+    println!(
+        "After {ticks} ticks: score {}-{}, ball at {position} moving {velocity}",
+        score.player1, score.player2,
+    );
+    Some(AppExit::Success)
+}
+
+/// A scripted change to a control during a headless match.
+#[derive(Copy, Clone, Debug)]
+pub struct ScriptedInput {
+    /// The tick on which to apply the change.
+    pub tick: u32,
+    pub slot: ControlSlot,
+    pub pressed: bool,
+}
+
+/// A match in [`Screen::Gameplay`] that can be stepped one tick at a time.
+///
+/// Configs default to their [`Default`] values instead of loading from the assets folder,
+/// so tests don't depend on tuning. Goal replays are turned off, since they pause the match.
+pub struct HeadlessMatch {
+    pub app: App,
+    pub tick: u32,
+    script: Vec<ScriptedInput>,
+}
+
+impl HeadlessMatch {
+    pub fn new(setup: MatchSetup) -> Self {
+        let mut app = App::new();
+        app.add_plugins(plugin);
+        app.insert_resource(setup);
+        app.finish();
+        app.cleanup();
+
+        Self {
+            app,
+            tick: 0,
+            script: vec![],
+        }
+        .with_config(ArenaConfig::default())
+        .with_config(BallConfig::default())
+        .with_config(PowerUpConfig::default())
+        .with_config(LayoutConfig::default())
+        .with_config(MatchConfig {
+            goal_replay_secs: 0.0,
+            ..default()
+        })
+    }
+
+    /// Use this config instead of the default.
+    pub fn with_config<C: Config>(mut self, config: C) -> Self {
+        let handle = self.app.world_mut().resource_mut::<Assets<C>>().add(config);
+        self.app.insert_resource(ConfigHandle(handle));
+        self
+    }
+
+    /// Run startup and enter gameplay.
+    pub fn start(mut self) -> Self {
+        self.app.update();
+        self.app
+            .world_mut()
+            .resource_mut::<NextStateBuffer<Screen>>()
+            .enter(Screen::Gameplay);
+        self.app.update();
+        self
+    }
+
+    /// Apply these inputs as the match steps forward.
+    pub fn with_script(mut self, script: impl IntoIterator<Item = ScriptedInput>) -> Self {
+        self.script.extend(script);
+        self
+    }
+
+    /// Step the match forward by a number of ticks.
+    pub fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.apply_script();
+            self.app.update();
+            self.tick += 1;
+        }
+    }
+
+    fn apply_script(&mut self) {
+        let world = self.app.world_mut();
+        let controls = world.resource::<Controls>();
+        let changes = self
+            .script
+            .iter()
+            .filter(|input| input.tick == self.tick)
+            .flat_map(|input| {
+                controls
+                    .get(input.slot)
+                    .keys
+                    .iter()
+                    .map(move |&key| (key, input.pressed))
+            })
+            .collect::<Vec<_>>();
+
+        let mut keys = world.resource_mut::<ButtonInput<KeyCode>>();
+        for (key, pressed) in changes {
+            if pressed {
+                keys.press(key);
+            } else {
+                keys.release(key);
+            }
+        }
+    }
+
+    pub fn score(&self) -> Score {
+        *self.app.world().resource::<Score>()
+    }
+
+    /// The ball's position and velocity.
+    pub fn ball(&mut self) -> (Vec2, Vec2) {
+        let (transform, velocity) = self
+            .app
+            .world_mut()
+            .query_filtered::<(&Transform, &LinearVelocity), With<Ball>>()
+            .single(self.app.world())
+            .unwrap();
+        (transform.translation.xy(), velocity.0)
+    }
+
+    /// Place the ball and set it moving.
+    pub fn set_ball(&mut self, position: Vec2, velocity: Vec2) {
+        let world = self.app.world_mut();
+        let (mut transform, mut linear_velocity) = world
+            .query_filtered::<(&mut Transform, &mut LinearVelocity), With<Ball>>()
+            .single_mut(world)
+            .unwrap();
+        transform.translation = position.extend(transform.translation.z);
+        linear_velocity.0 = velocity;
+    }
+
+    /// The y position of the paddle defending the given side.
+    pub fn paddle_y(&mut self, side: Side) -> f32 {
+        let world = self.app.world_mut();
+        world
+            .query::<(&Side, &Transform)>()
+            .iter(world)
+            .find(|(x, _)| **x == side)
+            .map(|(_, transform)| transform.translation.y)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::mode::PlayMode;
+
+    fn local_match() -> HeadlessMatch {
+        HeadlessMatch::new(MatchSetup {
+            mode: PlayMode::LocalVersus,
+            ..default()
+        })
+        .with_config(MatchConfig {
+            serve_countdown: 0.5,
+            goal_replay_secs: 0.0,
+            ..default()
+        })
+        .start()
+    }
+
+    #[test]
+    fn test_serve_launches_ball() {
+        let mut game = local_match();
+        let (_, velocity) = game.ball();
+        assert_eq!(velocity, Vec2::ZERO);
+
+        game.step(64);
+        let (position, velocity) = game.ball();
+        assert_ne!(velocity, Vec2::ZERO);
+        assert_ne!(position, Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_goal_scores_point() {
+        let mut game = local_match();
+        game.step(64);
+
+        // Send the ball past the left paddle into the goal.
+        game.set_ball(vec2(-450.0, 250.0), vec2(-400.0, 0.0));
+        game.step(20);
+
+        let score = game.score();
+        assert_eq!(score.get(Side::Left), 0);
+        assert_eq!(score.get(Side::Right), 1);
+        let (position, velocity) = game.ball();
+        assert_eq!(position, Vec2::ZERO);
+        assert_eq!(velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_same_seed_same_match() {
+        let run = || {
+            let mut game = HeadlessMatch::new(MatchSetup {
+                seed: Some(42),
+                ..default()
+            })
+            .start()
+            .with_script([ScriptedInput {
+                tick: 100,
+                slot: ControlSlot::Player1Down,
+                pressed: true,
+            }]);
+            game.step(1000);
+            (game.score(), game.ball())
+        };
+
+        let (score1, ball1) = run();
+        let (score2, ball2) = run();
+        assert_eq!(score1.get(Side::Left), score2.get(Side::Left));
+        assert_eq!(score1.get(Side::Right), score2.get(Side::Right));
+        assert_eq!(ball1, ball2);
+    }
+
+    #[test]
+    fn test_scripted_paddle_input() {
+        let mut game = local_match().with_script([
+            ScriptedInput {
+                tick: 0,
+                slot: ControlSlot::Player1Up,
+                pressed: true,
+            },
+            ScriptedInput {
+                tick: 10,
+                slot: ControlSlot::Player1Up,
+                pressed: false,
+            },
+        ]);
+
+        game.step(20);
+        assert!(game.paddle_y(Side::Left) > 0.0);
+        assert_eq!(game.paddle_y(Side::Right), 0.0);
+    }
+}
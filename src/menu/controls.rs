@@ -0,0 +1,234 @@
+use bevy::ecs::relationship::RelatedSpawner;
+
+use crate::core::input::ControlSlot;
+use crate::core::input::Controls;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        (
+            Menu::Controls.on_enter(spawn_controls_menu),
+            Menu::Controls.on_exit(stop_rebinding),
+        ),
+    );
+
+    app.configure::<(BindingCell, Rebinding, IsControlsStatus)>();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_controls_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::body(children![
+            widget::header("[b]Controls"),
+            grid(),
+            (
+                widget::label("Click a binding, then press a key or button."),
+                IsControlsStatus,
+            ),
+            widget::row_of_buttons(children![
+                widget::wide_button("Reset to defaults", reset_controls),
+                widget::button("Back", go_back),
+            ]),
+        ]));
+}
+
+fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.pop();
+}
+
+fn reset_controls(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut controls: ResMut<Controls>,
+    mut status_query: Query<&mut RichText, With<IsControlsStatus>>,
+) {
+    *controls = default();
+    commands.remove_resource::<Rebinding>();
+    for mut text in &mut status_query {
+        text.sections = parse_rich("Controls reset to defaults.");
+    }
+}
+
+fn grid() -> impl Bundle {
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            margin: UiRect::bottom(Vw(2.5)),
+            row_gap: Vw(1.0),
+            column_gap: Vw(3.0),
+            grid_template_columns: RepeatedGridTrack::auto(3),
+            ..default()
+        },
+        GridAlignment::columns([JustifySelf::End, JustifySelf::Center, JustifySelf::Center]),
+        Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<ChildOf>| {
+            parent.spawn(widget::label(""));
+            parent.spawn(widget::label("[b]Keyboard"));
+            parent.spawn(widget::label("[b]Gamepad"));
+
+            for slot in ControlSlot::ALL {
+                parent.spawn(widget::label(slot.name()));
+                for device in [InputDevice::Keyboard, InputDevice::Gamepad] {
+                    parent.spawn((
+                        widget::slim_button("", toggle_rebinding),
+                        BindingCell { slot, device },
+                    ));
+                }
+            }
+        })),
+    )
+}
+
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+enum InputDevice {
+    Keyboard,
+    Gamepad,
+}
+
+/// A button that shows and rebinds one control for one input device.
+#[derive(Component, Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+#[reflect(Component)]
+struct BindingCell {
+    slot: ControlSlot,
+    device: InputDevice,
+}
+
+impl Configure for BindingCell {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Controls.on_update(update_binding_cells.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_binding_cells(
+    controls: Res<Controls>,
+    rebinding: Option<Res<Rebinding>>,
+    cell_query: Query<(&BindingCell, &Children)>,
+    mut text_query: Query<&mut RichText>,
+) {
+    let target = rebinding.and_then(|x| x.target);
+    for (&cell, children) in &cell_query {
+        let label = *c!(children.first());
+        let text = if target == Some(cell) {
+            "[b]Press...".to_string()
+        } else {
+            let binding = controls.get(cell.slot);
+            match cell.device {
+                InputDevice::Keyboard => binding.keys_text(),
+                InputDevice::Gamepad => binding.buttons_text(),
+            }
+        };
+        c!(text_query.get_mut(label)).sections = parse_rich(text);
+    }
+}
+
+fn toggle_rebinding(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    rebinding: Option<Res<Rebinding>>,
+    cell_query: Query<&BindingCell>,
+) {
+    let cell = *r!(cell_query.get(r!(trigger.get_target())));
+    let target = if rebinding.and_then(|x| x.target) == Some(cell) {
+        None
+    } else {
+        Some(cell)
+    };
+    commands.insert_resource(Rebinding { target });
+}
+
+/// Waiting for a key or button press to rebind a control.
+///
+/// This lingers until all inputs are released, so the new binding doesn't trigger
+/// its action immediately.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct Rebinding {
+    target: Option<BindingCell>,
+}
+
+impl Configure for Rebinding {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Controls.on_update(
+                (capture_rebinding_input, finish_rebinding)
+                    .chain()
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(resource_exists::<Self>),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn capture_rebinding_input(
+    mut rebinding: ResMut<Rebinding>,
+    mut controls: ResMut<Controls>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    mut status_query: Query<&mut RichText, With<IsControlsStatus>>,
+) {
+    let cell = rq!(rebinding.target);
+    let conflict = match cell.device {
+        InputDevice::Keyboard => {
+            let &key = rq!(keys.get_just_pressed().next());
+            controls.rebind_key(cell.slot, key)
+        },
+        InputDevice::Gamepad => {
+            let &button = rq!(gamepad_query
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next()));
+            controls.rebind_button(cell.slot, button)
+        },
+    };
+    rebinding.target = None;
+
+    let message = match conflict {
+        Some(other) => format!("Already used by {}, swapped bindings.", other.name()),
+        None => format!("{} rebound.", cell.slot.name()),
+    };
+    for mut text in &mut status_query {
+        text.sections = parse_rich(&message);
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn finish_rebinding(
+    mut commands: Commands,
+    rebinding: Res<Rebinding>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+) {
+    rq!(rebinding.target.is_none());
+    rq!(keys.get_pressed().next().is_none());
+    rq!(gamepad_query
+        .iter()
+        .all(|gamepad| gamepad.get_pressed().next().is_none()));
+
+    commands.remove_resource::<Rebinding>();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn stop_rebinding(mut commands: Commands) {
+    commands.remove_resource::<Rebinding>();
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsControlsStatus;
+
+impl Configure for IsControlsStatus {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
@@ -1,3 +1,9 @@
+use crate::game::ai::CpuDifficulty;
+use crate::game::layout::LayoutConfig;
+use crate::game::mode::BallRule;
+use crate::game::mode::MatchSetup;
+use crate::game::mode::PlayMode;
+use crate::game::mode::cycle;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
@@ -5,7 +11,18 @@ use crate::screen::Screen;
 use crate::screen::fade::fade_out;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(StateFlush, Menu::Intro.on_enter(spawn_intro_menu));
+    app.add_systems(
+        StateFlush,
+        Menu::Intro.on_enter((leave_lan_mode, spawn_intro_menu)),
+    );
+
+    app.configure::<(
+        IsModeSelector,
+        IsHumansSelector,
+        IsDifficultySelector,
+        IsBallsSelector,
+        IsLayoutSelector,
+    )>();
 }
 
 #[cfg_attr(feature = "native_dev", hot)]
@@ -15,6 +32,7 @@ fn spawn_intro_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
         .with_child(widget::body(children![
             widget::header("[b]How to play"),
             widget::paragraph("Be skillful,\nwin the game!\nPress P to pause."),
+            grid(),
             widget::row_of_buttons(children![
                 widget::button("Back", go_back),
                 widget::button("Start", start_game)
@@ -22,6 +40,14 @@ fn spawn_intro_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
         ]));
 }
 
+/// LAN matches are started from their own menus instead.
+#[cfg_attr(feature = "native_dev", hot)]
+fn leave_lan_mode(mut setup: ResMut<MatchSetup>) {
+    if setup.mode == PlayMode::Lan {
+        setup.mode = default();
+    }
+}
+
 fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
     menu.pop();
 }
@@ -38,3 +64,285 @@ fn start_game(
         Screen::Loading
     }));
 }
+
+fn grid() -> impl Bundle {
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            margin: UiRect::bottom(Vw(2.5)),
+            row_gap: Vw(1.4),
+            column_gap: Vw(6.0),
+            grid_template_columns: vec![
+                RepeatedGridTrack::flex(1, 1.0),
+                RepeatedGridTrack::flex(1, 1.2),
+            ],
+            ..default()
+        },
+        GridAlignment::columns([JustifySelf::End, JustifySelf::Start]),
+        children![
+            widget::label("Mode"),
+            widget::selector(IsModeSelector, mode_left, mode_right),
+            widget::label("Humans"),
+            widget::selector(IsHumansSelector, humans_left, humans_right),
+            widget::label("CPU"),
+            widget::selector(IsDifficultySelector, difficulty_left, difficulty_right),
+            widget::label("Balls"),
+            widget::selector(IsBallsSelector, balls_left, balls_right),
+            widget::label("Layout"),
+            widget::selector(IsLayoutSelector, layout_left, layout_right),
+        ],
+    )
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsModeSelector;
+
+impl Configure for IsModeSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_mode_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_mode_selector(
+    setup: Res<MatchSetup>,
+    selector_query: Query<Entity, With<IsModeSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+) {
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(setup.mode.name());
+    }
+}
+
+fn mode_left(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    setup.mode = cycle(&PlayMode::ALL, setup.mode, -1);
+}
+
+fn mode_right(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    setup.mode = cycle(&PlayMode::ALL, setup.mode, 1);
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsHumansSelector;
+
+impl Configure for IsHumansSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_humans_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+/// The choices for how many of the four players are human.
+const HUMAN_PLAYERS: [usize; 5] = [0, 1, 2, 3, 4];
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_humans_selector(
+    setup: Res<MatchSetup>,
+    selector_query: Query<Entity, With<IsHumansSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    // The other modes have a fixed number of human players.
+    let disabled = !setup.is_four_player();
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.get(0));
+        c!(disabled_query.get_mut(left)).0 = disabled;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(if disabled {
+            "-".to_string()
+        } else {
+            setup.human_players.to_string()
+        });
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = disabled;
+    }
+}
+
+fn humans_left(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    rq!(setup.is_four_player());
+    setup.human_players = cycle(&HUMAN_PLAYERS, setup.human_players, -1);
+}
+
+fn humans_right(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    rq!(setup.is_four_player());
+    setup.human_players = cycle(&HUMAN_PLAYERS, setup.human_players, 1);
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsDifficultySelector;
+
+impl Configure for IsDifficultySelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_difficulty_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_difficulty_selector(
+    setup: Res<MatchSetup>,
+    selector_query: Query<Entity, With<IsDifficultySelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    // The difficulty only matters when playing against the CPU.
+    let disabled = !setup.has_cpu();
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.get(0));
+        c!(disabled_query.get_mut(left)).0 = disabled;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(if disabled {
+            "-"
+        } else {
+            setup.difficulty.name()
+        });
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = disabled;
+    }
+}
+
+fn difficulty_left(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    rq!(setup.has_cpu());
+    setup.difficulty = cycle(&CpuDifficulty::ALL, setup.difficulty, -1);
+}
+
+fn difficulty_right(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    rq!(setup.has_cpu());
+    setup.difficulty = cycle(&CpuDifficulty::ALL, setup.difficulty, 1);
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsBallsSelector;
+
+impl Configure for IsBallsSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_balls_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_balls_selector(
+    setup: Res<MatchSetup>,
+    selector_query: Query<Entity, With<IsBallsSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+) {
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(setup.balls.name());
+    }
+}
+
+fn balls_left(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    setup.balls = cycle(&BallRule::ALL, setup.balls, -1);
+}
+
+fn balls_right(_: Trigger<Pointer<Click>>, mut setup: ResMut<MatchSetup>) {
+    setup.balls = cycle(&BallRule::ALL, setup.balls, 1);
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsLayoutSelector;
+
+impl Configure for IsLayoutSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_layout_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_layout_selector(
+    config: ConfigRef<LayoutConfig>,
+    setup: Res<MatchSetup>,
+    selector_query: Query<Entity, With<IsLayoutSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+) {
+    let config = r!(config.get());
+    let name = config
+        .layouts
+        .get(setup.layout)
+        .map_or("-", |x| x.name.as_str());
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(name);
+    }
+}
+
+fn layout_left(
+    _: Trigger<Pointer<Click>>,
+    config: ConfigRef<LayoutConfig>,
+    mut setup: ResMut<MatchSetup>,
+) {
+    setup.layout = r!(config.get()).cycle(setup.layout, -1);
+}
+
+fn layout_right(
+    _: Trigger<Pointer<Click>>,
+    config: ConfigRef<LayoutConfig>,
+    mut setup: ResMut<MatchSetup>,
+) {
+    setup.layout = r!(config.get()).cycle(setup.layout, 1);
+}
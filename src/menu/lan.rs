@@ -0,0 +1,217 @@
+use std::net::IpAddr;
+use std::net::SocketAddr;
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::Key;
+use bevy::input::keyboard::KeyboardInput;
+
+use crate::game::netplay::DEFAULT_PORT;
+use crate::game::netplay::NetSession;
+use crate::game::netplay::NetStatus;
+use crate::game::netplay::local_ip;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::fade::fade_out;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        (
+            Menu::Host.on_edge(
+                cancel_waiting_session,
+                (start_hosting, spawn_host_menu).chain(),
+            ),
+            Menu::Join.on_edge(cancel_waiting_session, spawn_join_menu),
+        ),
+    );
+    app.add_systems(
+        Update,
+        Menu::with(|&x| matches!(x, Menu::Host | Menu::Join)).on_update(
+            start_lan_match
+                .in_set(UpdateSystems::Update)
+                .run_if(resource_exists::<NetSession>),
+        ),
+    );
+
+    app.configure::<(JoinAddress, IsJoinAddress, IsJoinStatus)>();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn start_hosting(mut commands: Commands) {
+    match NetSession::host(DEFAULT_PORT) {
+        Ok(session) => commands.insert_resource(session),
+        Err(e) => warn!("Failed to host a LAN game: {e}"),
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_host_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    session: Option<Res<NetSession>>,
+) {
+    let status = match session.and_then(|x| x.local_addr()) {
+        Some(addr) => match local_ip() {
+            Some(ip) => format!("Waiting for a player to join [b]{ip}:{}", addr.port()),
+            None => format!("Waiting for a player to join port [b]{}", addr.port()),
+        },
+        None => format!("Couldn't open port {DEFAULT_PORT}."),
+    };
+
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::body(children![
+            widget::header("[b]Host LAN game"),
+            widget::label(status),
+            widget::row_of_buttons(children![widget::button("Back", go_back)]),
+        ]));
+}
+
+/// Cancel the session when leaving the menu, unless the match is starting.
+#[cfg_attr(feature = "native_dev", hot)]
+fn cancel_waiting_session(mut commands: Commands, session: Option<Res<NetSession>>) {
+    rq!(session.is_some_and(|x| x.status == NetStatus::Waiting));
+    commands.remove_resource::<NetSession>();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn start_lan_match(mut commands: Commands, session: Res<NetSession>, mut menu: NextMut<Menu>) {
+    rq!(session.status == NetStatus::Connected);
+    menu.disable();
+    commands.spawn(fade_out(Screen::Gameplay));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_join_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::body(children![
+            widget::header("[b]Join LAN game"),
+            (widget::big_label(""), IsJoinAddress),
+            (
+                widget::label("Type the host's address, then connect."),
+                IsJoinStatus,
+            ),
+            widget::row_of_buttons(children![
+                widget::button("Back", go_back),
+                widget::button("Connect", connect),
+            ]),
+        ]));
+}
+
+fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.pop();
+}
+
+fn connect(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    address: Res<JoinAddress>,
+    mut status_query: Query<&mut RichText, With<IsJoinStatus>>,
+) {
+    let message = match address.parse() {
+        Some(host) => match NetSession::join(host) {
+            Ok(session) => {
+                commands.insert_resource(session);
+                format!("Connecting to {host}...")
+            },
+            Err(e) => format!("Couldn't connect: {e}"),
+        },
+        None => "That's not a valid address.".to_string(),
+    };
+    for mut text in &mut status_query {
+        text.sections = parse_rich(&message);
+    }
+}
+
+/// The host address typed into the join menu.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+struct JoinAddress(String);
+
+impl Configure for JoinAddress {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Join.on_update(type_join_address.in_set(UpdateSystems::RecordInput)),
+        );
+    }
+}
+
+impl Default for JoinAddress {
+    fn default() -> Self {
+        Self(format!("127.0.0.1:{DEFAULT_PORT}"))
+    }
+}
+
+impl JoinAddress {
+    const MAX_LEN: usize = 64;
+
+    /// The address to connect to, using the default port if none is given.
+    fn parse(&self) -> Option<SocketAddr> {
+        self.0.parse().ok().or_else(|| {
+            self.0
+                .parse::<IpAddr>()
+                .ok()
+                .map(|ip| SocketAddr::new(ip, DEFAULT_PORT))
+        })
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn type_join_address(mut key_events: EventReader<KeyboardInput>, mut address: ResMut<JoinAddress>) {
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Backspace => {
+                address.0.pop();
+            },
+            Key::Character(text) if address.0.len() < JoinAddress::MAX_LEN => {
+                address
+                    .0
+                    .extend(text.chars().filter(char::is_ascii_graphic));
+            },
+            _ => {},
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsJoinAddress;
+
+impl Configure for IsJoinAddress {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Join.on_update(update_join_address.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_join_address(
+    address: Res<JoinAddress>,
+    mut label_query: Query<&mut RichText, With<IsJoinAddress>>,
+) {
+    for mut label in &mut label_query {
+        label.sections = parse_rich(format!("{}_", address.0));
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsJoinStatus;
+
+impl Configure for IsJoinStatus {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
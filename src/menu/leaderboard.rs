@@ -0,0 +1,308 @@
+use bevy::ecs::relationship::RelatedSpawner;
+use bevy::input::mouse::MouseScrollUnit;
+use bevy::input::mouse::MouseWheel;
+
+use crate::game::history::HistorySort;
+use crate::game::history::MatchHistory;
+use crate::game::mode::PlayMode;
+use crate::game::mode::cycle;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        Menu::Leaderboard.on_enter(spawn_leaderboard_menu),
+    );
+
+    app.configure::<(
+        LeaderboardView,
+        IsLeaderboardModeSelector,
+        IsLeaderboardSortSelector,
+        IsLeaderboardList,
+    )>();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_leaderboard_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::body(children![
+            widget::header("[b]Leaderboard"),
+            grid(),
+            (
+                Name::new("MatchList"),
+                Node {
+                    width: Percent(100.0),
+                    max_height: Vh(40.0),
+                    overflow: Overflow::scroll_y(),
+                    ..Node::COLUMN
+                },
+                IsLeaderboardList,
+            ),
+            widget::row_of_buttons(children![widget::button("Back", go_back)]),
+        ]));
+}
+
+fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.pop();
+}
+
+fn grid() -> impl Bundle {
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            margin: UiRect::bottom(Vw(2.5)),
+            row_gap: Vw(1.4),
+            column_gap: Vw(6.0),
+            grid_template_columns: vec![
+                RepeatedGridTrack::flex(1, 1.0),
+                RepeatedGridTrack::flex(1, 1.2),
+            ],
+            ..default()
+        },
+        GridAlignment::columns([JustifySelf::End, JustifySelf::Start]),
+        children![
+            widget::label("Mode"),
+            widget::selector(IsLeaderboardModeSelector, mode_left, mode_right),
+            widget::label("Sort by"),
+            widget::selector(IsLeaderboardSortSelector, sort_left, sort_right),
+        ],
+    )
+}
+
+/// How the past matches are filtered and ordered.
+#[derive(Resource, Reflect, Copy, Clone, Default, Debug)]
+#[reflect(Resource)]
+struct LeaderboardView {
+    /// The mode to show, or `None` for every mode.
+    mode: Option<PlayMode>,
+    sort: HistorySort,
+}
+
+impl Configure for LeaderboardView {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl LeaderboardView {
+    /// Every mode to filter by, starting with `None` for all of them.
+    fn modes() -> Vec<Option<PlayMode>> {
+        std::iter::once(None)
+            .chain(PlayMode::EVERY.map(Some))
+            .collect()
+    }
+
+    fn mode_name(&self) -> &'static str {
+        self.mode.map_or("All", PlayMode::name)
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsLeaderboardModeSelector;
+
+impl Configure for IsLeaderboardModeSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Leaderboard.on_update(update_mode_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_mode_selector(
+    view: Res<LeaderboardView>,
+    selector_query: Query<Entity, With<IsLeaderboardModeSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+) {
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(view.mode_name());
+    }
+}
+
+fn mode_left(_: Trigger<Pointer<Click>>, mut view: ResMut<LeaderboardView>) {
+    view.mode = cycle(&LeaderboardView::modes(), view.mode, -1);
+}
+
+fn mode_right(_: Trigger<Pointer<Click>>, mut view: ResMut<LeaderboardView>) {
+    view.mode = cycle(&LeaderboardView::modes(), view.mode, 1);
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsLeaderboardSortSelector;
+
+impl Configure for IsLeaderboardSortSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Leaderboard.on_update(update_sort_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_sort_selector(
+    view: Res<LeaderboardView>,
+    selector_query: Query<Entity, With<IsLeaderboardSortSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+) {
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(view.sort.name());
+    }
+}
+
+fn sort_left(_: Trigger<Pointer<Click>>, mut view: ResMut<LeaderboardView>) {
+    view.sort = cycle(&HistorySort::ALL, view.sort, -1);
+}
+
+fn sort_right(_: Trigger<Pointer<Click>>, mut view: ResMut<LeaderboardView>) {
+    view.sort = cycle(&HistorySort::ALL, view.sort, 1);
+}
+
+/// The scrollable list of past matches.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsLeaderboardList;
+
+impl Configure for IsLeaderboardList {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Leaderboard.on_update((
+                scroll_leaderboard_list.in_set(UpdateSystems::RecordInput),
+                update_leaderboard_list.in_set(UpdateSystems::Update),
+            )),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn scroll_leaderboard_list(
+    mut wheel_events: EventReader<MouseWheel>,
+    mut list_query: Query<&mut ScrollPosition, With<IsLeaderboardList>>,
+) {
+    // The UI layout clamps the scroll position to the list's contents.
+    let dy = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * 24.0,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum::<f32>();
+    rq!(dy != 0.0);
+    for mut scroll in &mut list_query {
+        scroll.offset_y = (scroll.offset_y - dy).max(0.0);
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_leaderboard_list(
+    mut commands: Commands,
+    view: Res<LeaderboardView>,
+    history: Res<MatchHistory>,
+    list_query: Query<(Entity, Ref<IsLeaderboardList>)>,
+) {
+    for (entity, list) in &list_query {
+        if !list.is_added() && !view.is_changed() && !history.is_changed() {
+            continue;
+        }
+
+        let rows = history
+            .list(view.mode, view.sort)
+            .into_iter()
+            .map(|record| {
+                let secs = record.duration.as_secs();
+                let [left, right] = &record.players;
+                let result = match &record.placements {
+                    // List free-for-all sides by placement, with their lives left.
+                    Some(placements) => {
+                        let mut placements = placements.iter().collect::<Vec<_>>();
+                        placements.sort_by_key(|x| x.place);
+                        placements
+                            .iter()
+                            .map(|x| format!("{}. {} [b]{}[r]", x.place, x.name, x.lives))
+                            .collect::<Vec<_>>()
+                            .join("   ")
+                    },
+                    None => format!(
+                        "{left}  [b]{} - {}[r]  {right}",
+                        record.score.player1, record.score.player2,
+                    ),
+                };
+                [
+                    record.date_string(),
+                    record.mode.name().to_string(),
+                    result,
+                    format!("{}:{:02}", secs / 60, secs % 60),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .insert(ScrollPosition::default())
+            .with_child(match_table(rows));
+    }
+}
+
+fn match_table(rows: Vec<[String; 4]>) -> impl Bundle {
+    (
+        Name::new("MatchTable"),
+        Node {
+            display: Display::Grid,
+            align_self: AlignSelf::Center,
+            row_gap: Vw(0.6),
+            column_gap: Vw(3.0),
+            grid_template_columns: RepeatedGridTrack::auto(4),
+            ..default()
+        },
+        GridAlignment::columns([
+            JustifySelf::Start,
+            JustifySelf::Start,
+            JustifySelf::Center,
+            JustifySelf::End,
+        ]),
+        Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<ChildOf>| {
+            if rows.is_empty() {
+                parent.spawn(widget::label("No matches yet."));
+                return;
+            }
+
+            for header in ["Date", "Mode", "Score", "Time"] {
+                parent.spawn(widget::label(format!("[b]{header}")));
+            }
+            for row in rows {
+                for cell in row {
+                    parent.spawn(widget::label(cell));
+                }
+            }
+        })),
+    )
+}
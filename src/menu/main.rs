@@ -1,6 +1,10 @@
+use crate::game::replay::Replay;
+use crate::game::replay::ReplayPlayback;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::fade::fade_out;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(StateFlush, Menu::Main.on_enter(spawn_main_menu));
@@ -14,6 +18,22 @@ fn spawn_main_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
             widget::header("[b]Pre Jam Practice"),
             widget::column_of_buttons(children![
                 widget::big_button("Play", open_intro),
+                (
+                    widget::big_button("Host LAN", open_host),
+                    #[cfg(feature = "web")]
+                    InteractionDisabled(true),
+                ),
+                (
+                    widget::big_button("Join LAN", open_join),
+                    #[cfg(feature = "web")]
+                    InteractionDisabled(true),
+                ),
+                (
+                    widget::big_button("Last replay", watch_last_replay),
+                    #[cfg(not(feature = "native"))]
+                    InteractionDisabled(true),
+                ),
+                widget::big_button("Leaderboard", open_leaderboard),
                 widget::big_button("Settings", open_settings),
                 (
                     widget::big_button("Quit", quit_to_desktop),
@@ -28,6 +48,24 @@ fn open_intro(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>
     menu.push(Menu::Intro);
 }
 
+fn open_host(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.push(Menu::Host);
+}
+
+fn open_join(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.push(Menu::Join);
+}
+
+fn watch_last_replay(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    let replay = r!(Replay::load_latest());
+    commands.insert_resource(ReplayPlayback::new(replay));
+    commands.spawn(fade_out(Screen::Replay));
+}
+
+fn open_leaderboard(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.push(Menu::Leaderboard);
+}
+
 fn open_settings(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
     menu.push(Menu::Settings);
 }
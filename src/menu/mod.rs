@@ -1,8 +1,14 @@
+mod controls;
 mod intro;
+mod lan;
+mod leaderboard;
 mod main;
 mod pause;
 mod settings;
 
+use crate::core::input::ControlSlot;
+use crate::core::input::Controls;
+use crate::menu::controls::Rebinding;
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
@@ -44,8 +50,12 @@ impl FromWorld for MenuRoot {
 pub enum Menu {
     Main,
     Intro,
+    Host,
+    Join,
+    Leaderboard,
     Pause,
     Settings,
+    Controls,
 }
 
 impl Configure for Menu {
@@ -59,7 +69,15 @@ impl Configure for Menu {
                 Menu::ANY.on_enable((Pause::enable_default, spawn_menu_overlay)),
             ),
         );
-        app.add_plugins((main::plugin, intro::plugin, pause::plugin, settings::plugin));
+        app.add_plugins((
+            main::plugin,
+            intro::plugin,
+            lan::plugin,
+            leaderboard::plugin,
+            pause::plugin,
+            settings::plugin,
+            controls::plugin,
+        ));
     }
 }
 
@@ -79,19 +97,28 @@ pub enum MenuAction {
 impl Configure for MenuAction {
     fn configure(app: &mut App) {
         app.init_resource::<ActionState<Self>>();
-        app.insert_resource(
-            InputMap::default()
-                .with(Self::Back, GamepadButton::South)
-                .with(Self::Back, KeyCode::Escape),
-        );
+        app.init_resource::<InputMap<Self>>();
         app.add_plugins(InputManagerPlugin::<Self>::default());
         app.add_systems(
             Update,
-            Menu::with(|&x| x != Menu::Main).on_update(
-                Menu::pop
-                    .in_set(UpdateSystems::RecordInput)
-                    .run_if(action_just_pressed(Self::Back)),
+            (
+                apply_menu_controls
+                    .run_if(resource_changed::<Controls>)
+                    .in_set(UpdateSystems::Update),
+                Menu::with(|&x| x != Menu::Main).on_update(
+                    Menu::pop.in_set(UpdateSystems::RecordInput).run_if(
+                        action_just_pressed(Self::Back).and(not(resource_exists::<Rebinding>)),
+                    ),
+                ),
             ),
         );
     }
 }
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_menu_controls(controls: Res<Controls>, mut input_map: ResMut<InputMap<MenuAction>>) {
+    *input_map = default();
+    controls
+        .get(ControlSlot::MenuBack)
+        .insert_into(&mut input_map, MenuAction::Back);
+}
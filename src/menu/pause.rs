@@ -1,3 +1,7 @@
+use crate::game::mode::MatchSetup;
+use crate::game::rules::MatchClock;
+use crate::game::stats::MatchStats;
+use crate::game::stats::stats_panel;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
@@ -9,11 +13,18 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 #[cfg_attr(feature = "native_dev", hot)]
-fn spawn_pause_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
+fn spawn_pause_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    stats: Res<MatchStats>,
+    setup: Res<MatchSetup>,
+    clock: Res<MatchClock>,
+) {
     commands
         .entity(menu_root.ui)
         .with_child(widget::body(children![
             widget::header("[b]Game paused"),
+            stats_panel(&stats, &setup, clock.0),
             widget::column_of_buttons(children![
                 widget::wide_button("Settings", open_settings),
                 widget::wide_button("Continue", disable_menu),
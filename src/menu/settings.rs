@@ -2,6 +2,7 @@ use bevy_simple_prefs::Prefs;
 use bevy_simple_prefs::PrefsPlugin;
 
 use crate::core::audio::AudioSettings;
+use crate::core::input::Controls;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
@@ -14,6 +15,7 @@ pub(super) fn plugin(app: &mut App) {
         IsMasterVolumeSelector,
         IsMusicVolumeSelector,
         IsUiVolumeSelector,
+        IsSfxVolumeSelector,
     )>();
 }
 
@@ -24,10 +26,17 @@ fn spawn_settings_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
         .with_child(widget::body(children![
             widget::header("[b]Settings"),
             grid(),
-            widget::row_of_buttons(children![widget::wide_button("Back", go_back)]),
+            widget::row_of_buttons(children![
+                widget::button("Controls", open_controls),
+                widget::button("Back", go_back),
+            ]),
         ]));
 }
 
+fn open_controls(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.push(Menu::Controls);
+}
+
 fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
     menu.pop();
 }
@@ -54,6 +63,8 @@ fn grid() -> impl Bundle {
             widget::selector(IsMusicVolumeSelector, music_volume_down, music_volume_up),
             widget::label("UI volume"),
             widget::selector(IsUiVolumeSelector, ui_volume_down, ui_volume_up),
+            widget::label("SFX volume"),
+            widget::selector(IsSfxVolumeSelector, sfx_volume_down, sfx_volume_up),
         ],
     )
 }
@@ -205,21 +216,65 @@ fn ui_volume_up(_: Trigger<Pointer<Click>>, mut audio_settings: ResMut<AudioSett
     audio_settings.ui_volume = (audio_settings.ui_volume + 0.1).min(1.0);
 }
 
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsSfxVolumeSelector;
+
+impl Configure for IsSfxVolumeSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Settings.on_update(update_sfx_volume_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_sfx_volume_selector(
+    audio_settings: Res<AudioSettings>,
+    selector_query: Query<Entity, With<IsSfxVolumeSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.get(0));
+        c!(disabled_query.get_mut(left)).0 = audio_settings.sfx_volume <= f32::EPSILON;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections =
+            parse_rich(format!("{:.0}%", audio_settings.sfx_volume * 100.0));
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = audio_settings.sfx_volume >= 1.0 - f32::EPSILON;
+    }
+}
+
+fn sfx_volume_down(_: Trigger<Pointer<Click>>, mut audio_settings: ResMut<AudioSettings>) {
+    audio_settings.sfx_volume = (audio_settings.sfx_volume - 0.1).max(0.0);
+}
+
+fn sfx_volume_up(_: Trigger<Pointer<Click>>, mut audio_settings: ResMut<AudioSettings>) {
+    audio_settings.sfx_volume = (audio_settings.sfx_volume + 0.1).min(1.0);
+}
+
 #[derive(Prefs, Reflect, Default)]
 struct Settings {
     pub audio_settings: AudioSettings,
+    pub controls: Controls,
 }
 
 impl Configure for Settings {
     fn configure(app: &mut App) {
-        // Create the config folder if necessary.
         #[cfg(feature = "native")]
-        let path = {
-            let path = r!(dirs::config_local_dir()).join(env!("CARGO_PKG_NAME"));
-            r!(std::fs::create_dir_all(&path).is_ok());
-            r!(std::fs::exists(&path));
-            path
-        };
+        let path = r!(crate::util::config_local_dir());
 
         // If there were no issues, initialize settings.
         app.add_plugins(PrefsPlugin::<Settings> {
@@ -1,5 +1,8 @@
 use crate::core::audio::AudioSettings;
 use crate::core::audio::music_audio;
+use crate::core::input::ControlSlot;
+use crate::core::input::Controls;
+use crate::game::netplay::NetSession;
 use crate::menu::Menu;
 use crate::prelude::*;
 use crate::screen::Screen;
@@ -26,6 +29,14 @@ fn spawn_gameplay_screen(
 pub struct GameplayAssets {
     #[asset(path = "audio/music/545458__bertsz__bit-forest-evil-theme-music.ogg")]
     music: Handle<AudioSource>,
+    #[asset(path = "audio/sfx/paddle_hit.wav")]
+    pub paddle_hit: Handle<AudioSource>,
+    #[asset(path = "audio/sfx/wall_hit.wav")]
+    pub wall_hit: Handle<AudioSource>,
+    #[asset(path = "audio/sfx/goal.wav")]
+    pub goal: Handle<AudioSource>,
+    #[asset(path = "audio/sfx/serve.wav")]
+    pub serve: Handle<AudioSource>,
 }
 
 impl Configure for GameplayAssets {
@@ -38,29 +49,45 @@ impl Configure for GameplayAssets {
 #[derive(Actionlike, Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum GameplayAction {
     TogglePause,
+    SkipReplay,
 }
 
 impl Configure for GameplayAction {
     fn configure(app: &mut App) {
         app.init_resource::<ActionState<Self>>();
-        app.insert_resource(
-            InputMap::default()
-                .with(Self::TogglePause, GamepadButton::Start)
-                .with(Self::TogglePause, KeyCode::Escape)
-                .with(Self::TogglePause, KeyCode::KeyP),
-        );
+        app.init_resource::<InputMap<Self>>();
         app.add_plugins(InputManagerPlugin::<Self>::default());
         app.add_systems(
             Update,
-            Screen::Gameplay.on_update(
-                Menu::Pause
-                    .toggle()
-                    .in_set(UpdateSystems::RecordInput)
-                    .run_if(
-                        action_just_pressed(Self::TogglePause)
-                            .and(Menu::is_disabled.or(Menu::Pause.will_update())),
-                    ),
+            (
+                apply_gameplay_controls
+                    .run_if(resource_changed::<Controls>)
+                    .in_set(UpdateSystems::Update),
+                Screen::Gameplay.on_update(
+                    Menu::Pause
+                        .toggle()
+                        .in_set(UpdateSystems::RecordInput)
+                        .run_if(
+                            action_just_pressed(Self::TogglePause)
+                                .and(Menu::is_disabled.or(Menu::Pause.will_update()))
+                                .and(not(resource_exists::<NetSession>)),
+                        ),
+                ),
             ),
         );
     }
 }
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_gameplay_controls(
+    controls: Res<Controls>,
+    mut input_map: ResMut<InputMap<GameplayAction>>,
+) {
+    *input_map = default();
+    controls
+        .get(ControlSlot::TogglePause)
+        .insert_into(&mut input_map, GameplayAction::TogglePause);
+    controls
+        .get(ControlSlot::SkipReplay)
+        .insert_into(&mut input_map, GameplayAction::SkipReplay);
+}
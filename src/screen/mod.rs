@@ -1,6 +1,9 @@
 pub mod fade;
-mod gameplay;
+pub mod gameplay;
 mod loading;
+mod replay;
+mod results;
+mod spectate;
 mod splash;
 mod title;
 
@@ -57,6 +60,9 @@ pub enum Screen {
     Title,
     Loading,
     Gameplay,
+    Results,
+    Replay,
+    Spectate,
 }
 
 impl Configure for Screen {
@@ -76,6 +82,9 @@ impl Configure for Screen {
             title::plugin,
             loading::plugin,
             gameplay::plugin,
+            results::plugin,
+            replay::plugin,
+            spectate::plugin,
         ));
     }
 }
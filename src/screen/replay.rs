@@ -0,0 +1,134 @@
+use crate::game::mode::cycle;
+use crate::game::replay::ReplayPlayback;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+use crate::screen::fade::fade_out;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Screen::Replay.on_enter(spawn_replay_screen));
+
+    app.configure::<IsReplayStatus>();
+}
+
+/// How far the seek buttons jump, in seconds.
+const SEEK_SECS: f32 = 5.0;
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_replay_screen(mut commands: Commands, screen_root: Res<ScreenRoot>) {
+    commands.entity(screen_root.ui).with_child((
+        Name::new("ReplayControls"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceBetween,
+            // Leave room for the HUD at the top.
+            padding: UiRect::new(Vw(2.0), Vw(2.0), Vw(11.0), Vw(2.0)),
+            ..Node::DEFAULT.full_size().abs()
+        },
+        Pickable::IGNORE,
+        children![
+            widget::big_label("[b]REPLAY"),
+            (
+                Name::new("Controls"),
+                Node::COLUMN_CENTER,
+                children![
+                    widget::row_of_buttons(children![
+                        widget::small_button("<", seek_backward),
+                        widget::small_button("-", slow_down),
+                        (widget::label(""), IsReplayStatus),
+                        widget::small_button("+", speed_up),
+                        widget::small_button(">", seek_forward),
+                    ]),
+                    widget::row_of_buttons(children![
+                        widget::button("Play / Pause", toggle_paused),
+                        widget::button("Exit replay", exit_replay),
+                    ]),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn seek_backward(
+    _: Trigger<Pointer<Click>>,
+    mut playback: ResMut<ReplayPlayback>,
+    time: Res<Time<Fixed>>,
+    mut screen: NextMut<Screen>,
+) {
+    let ticks = (SEEK_SECS / time.timestep().as_secs_f32()) as u32;
+    playback.seek_to = Some(playback.tick.saturating_sub(ticks));
+    // Restart the replay, then play forward to the target.
+    screen.trigger().enter(Screen::Replay);
+}
+
+fn seek_forward(
+    _: Trigger<Pointer<Click>>,
+    mut playback: ResMut<ReplayPlayback>,
+    time: Res<Time<Fixed>>,
+) {
+    let ticks = (SEEK_SECS / time.timestep().as_secs_f32()) as u32;
+    playback.seek_to = Some(playback.tick + ticks);
+}
+
+fn slow_down(_: Trigger<Pointer<Click>>, mut playback: ResMut<ReplayPlayback>) {
+    playback.speed = cycle(&ReplayPlayback::SPEEDS, playback.speed, -1).min(playback.speed);
+}
+
+fn speed_up(_: Trigger<Pointer<Click>>, mut playback: ResMut<ReplayPlayback>) {
+    playback.speed = cycle(&ReplayPlayback::SPEEDS, playback.speed, 1).max(playback.speed);
+}
+
+fn toggle_paused(_: Trigger<Pointer<Click>>, mut playback: ResMut<ReplayPlayback>) {
+    playback.paused = !playback.paused;
+}
+
+fn exit_replay(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+) {
+    // Let the fade out play at normal speed.
+    playback.paused = false;
+    playback.speed = 1.0;
+    commands.spawn(fade_out(Screen::Title));
+}
+
+/// The label that shows the replay time and speed.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsReplayStatus;
+
+impl Configure for IsReplayStatus {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Replay.on_update(update_replay_status.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_replay_status(
+    playback: Res<ReplayPlayback>,
+    time: Res<Time<Fixed>>,
+    mut label_query: Query<&mut RichText, With<IsReplayStatus>>,
+) {
+    let timestep = time.timestep().as_secs_f32();
+    let format_time = |ticks: u32| {
+        let secs = (ticks as f32 * timestep) as u32;
+        format!("{}:{:02}", secs / 60, secs % 60)
+    };
+    let text = format!(
+        "{} / {}  [b]{}x{}",
+        format_time(playback.tick.min(playback.tick_count())),
+        format_time(playback.tick_count()),
+        playback.speed,
+        if playback.paused { " (paused)" } else { "" },
+    );
+
+    for mut label in &mut label_query {
+        label.sections = parse_rich(&text);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::game::mode::MatchSetup;
+use crate::game::mode::PlayMode;
+use crate::game::replay::LastReplay;
+use crate::game::replay::ReplayPlayback;
+use crate::game::rules::Lives;
+use crate::game::rules::MatchResult;
+use crate::game::stats::MatchStats;
+use crate::game::stats::stats_panel;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+use crate::screen::fade::fade_out;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Screen::Results.on_enter(spawn_results_screen));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_results_screen(
+    mut commands: Commands,
+    screen_root: Res<ScreenRoot>,
+    result: Res<MatchResult>,
+    setup: Res<MatchSetup>,
+    stats: Res<MatchStats>,
+    lives: Res<Lives>,
+) {
+    let score = if setup.is_free_for_all() {
+        setup
+            .sides()
+            .iter()
+            .map(|&side| lives.get(side).to_string())
+            .collect::<Vec<_>>()
+            .join(" - ")
+    } else {
+        format!("{} - {}", result.score.player1, result.score.player2)
+    };
+
+    commands
+        .entity(screen_root.ui)
+        .with_child(widget::body(children![
+            widget::header(format!("[b]{} wins!", setup.side_name(result.winner))),
+            widget::big_label(score),
+            stats_panel(&stats, &setup, result.duration),
+            widget::row_of_buttons(children![
+                (
+                    widget::button("Rematch", rematch),
+                    // A LAN rematch needs both players to connect again.
+                    InteractionDisabled(setup.mode == PlayMode::Lan),
+                ),
+                widget::button("Watch replay", watch_replay),
+                widget::button("Quit to title", quit_to_title),
+            ]),
+        ]));
+}
+
+fn rematch(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.spawn(fade_out(Screen::Gameplay));
+}
+
+fn watch_replay(_: Trigger<Pointer<Click>>, mut commands: Commands, last_replay: Res<LastReplay>) {
+    let replay = r!(last_replay.0.clone());
+    commands.insert_resource(ReplayPlayback::new(replay));
+    commands.spawn(fade_out(Screen::Replay));
+}
+
+fn quit_to_title(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.spawn(fade_out(Screen::Title));
+}
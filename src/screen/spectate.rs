@@ -0,0 +1,547 @@
+use std::collections::VecDeque;
+use std::io;
+use std::io::Read as _;
+use std::net::SocketAddr;
+use std::net::TcpStream;
+
+use bevy::tasks::IoTaskPool;
+use bevy::tasks::Task;
+use bevy::tasks::futures_lite::future;
+
+use crate::game::Boundary;
+use crate::game::Side;
+use crate::game::arena::ArenaConfig;
+use crate::game::broadcast::BROADCAST_PORT;
+use crate::game::broadcast::BroadcastEvent;
+use crate::game::broadcast::BroadcastMessage;
+use crate::game::broadcast::BroadcastPaddle;
+use crate::game::layout::LayoutConfig;
+use crate::game::layout::Obstacle;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+use crate::screen::fade::fade_out;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        Screen::Spectate.on_edge(stop_spectating, (start_spectating, spawn_spectate_screen)),
+    );
+    app.add_systems(
+        Update,
+        Screen::Spectate.on_update((
+            (connect_to_broadcast, receive_broadcast)
+                .chain()
+                .in_set(UpdateSystems::RecordInput),
+            (draw_spectated_match, update_spectate_labels).in_set(UpdateSystems::Update),
+        )),
+    );
+
+    app.configure::<(SpectateTarget, IsSpectateScore, IsSpectateStatus)>();
+}
+
+/// How far behind the broadcast to render, in seconds, so there are frames to interpolate.
+const INTERPOLATION_DELAY: f32 = 0.1;
+
+/// How often to try connecting again, in seconds.
+const RETRY_SECS: f32 = 1.0;
+
+/// The broadcast to watch, from the `--spectate [address]` command-line flag.
+///
+/// If the flag is given, the game goes straight to [`Screen::Spectate`] after the splash screen.
+/// A match on another machine can only be watched if that game was run with `--broadcast-lan`.
+#[derive(Resource, Default)]
+pub struct SpectateTarget(pub Option<SocketAddr>);
+
+impl Configure for SpectateTarget {
+    fn configure(app: &mut App) {
+        app.insert_resource(Self::from_args(std::env::args()));
+    }
+}
+
+impl SpectateTarget {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter().skip_while(|x| x != "--spectate");
+        if args.next().is_none() {
+            return Self(None);
+        }
+
+        let default = SocketAddr::from(([127, 0, 0, 1], BROADCAST_PORT));
+        Self(Some(args.next().map_or(default, |x| {
+            x.parse()
+                .or_else(|_| x.parse().map(|ip| SocketAddr::new(ip, BROADCAST_PORT)))
+                .unwrap_or(default)
+        })))
+    }
+}
+
+/// The connection to a broadcasting match.
+#[derive(Resource)]
+struct Spectator {
+    addr: SocketAddr,
+    stream: Option<TcpStream>,
+    /// The connection attempt in progress, if any.
+    connecting: Option<Task<io::Result<TcpStream>>>,
+    retry: Timer,
+    /// Bytes received after the last complete line.
+    partial: Vec<u8>,
+    /// The display name for each side in the match.
+    names: Vec<(Side, String)>,
+    /// Each side's score, or its lives in free-for-all matches, in the same order as the names.
+    scores: Vec<u32>,
+    /// The latest events, to show in the status label.
+    last_event: Option<BroadcastEvent>,
+    /// The received frames and when they arrived, oldest first.
+    frames: VecDeque<SpectatedFrame>,
+    /// The spectator's own arena config, to restore afterwards.
+    live_arena: Option<ArenaConfig>,
+}
+
+struct SpectatedFrame {
+    time: f32,
+    balls: Vec<Vec2>,
+    paddles: Vec<BroadcastPaddle>,
+    obstacles: Vec<(Vec2, f32)>,
+}
+
+impl Spectator {
+    fn new(addr: SocketAddr) -> Self {
+        let mut retry = Timer::from_seconds(RETRY_SECS, TimerMode::Repeating);
+        retry.tick(retry.duration());
+        Self {
+            addr,
+            stream: None,
+            connecting: None,
+            retry,
+            partial: vec![],
+            names: [Side::Left, Side::Right]
+                .map(|x| (x, x.player_name().to_string()))
+                .into(),
+            scores: vec![0; 2],
+            last_event: None,
+            frames: VecDeque::new(),
+            live_arena: None,
+        }
+    }
+
+    /// Read every complete line that has arrived, or `Err` if the connection was lost.
+    fn read_lines(&mut self) -> io::Result<Vec<String>> {
+        let Some(stream) = self.stream.as_mut() else {
+            return Ok(vec![]);
+        };
+        let mut buf = [0; 4096];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) => return Err(io::ErrorKind::ConnectionAborted.into()),
+                Ok(n) => self.partial.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut lines = vec![];
+        while let Some(i) = self.partial.iter().position(|&x| x == b'\n') {
+            let line = self.partial.drain(..=i).collect::<Vec<_>>();
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+        Ok(lines)
+    }
+
+    /// The ball, paddle, and obstacle poses at the given time, interpolated between frames.
+    fn sample(&self, time: f32) -> Option<SpectatedFrame> {
+        let i = self.frames.iter().rposition(|x| x.time <= time)?;
+        let prev = &self.frames[i];
+        let Some(next) = self.frames.get(i + 1) else {
+            return Some(SpectatedFrame {
+                time,
+                balls: prev.balls.clone(),
+                paddles: prev.paddles.clone(),
+                obstacles: prev.obstacles.clone(),
+            });
+        };
+
+        let t = ((time - prev.time) / (next.time - prev.time)).clamp(0.0, 1.0);
+        // Don't slide a ball across the arena when it's reset for a serve.
+        let balls = if prev.balls.len() == next.balls.len() {
+            prev.balls
+                .iter()
+                .zip(&next.balls)
+                .map(|(a, b)| {
+                    if a.distance(*b) > 100.0 {
+                        *b
+                    } else {
+                        a.lerp(*b, t)
+                    }
+                })
+                .collect()
+        } else {
+            next.balls.clone()
+        };
+        let paddles = if prev.paddles.len() == next.paddles.len() {
+            prev.paddles
+                .iter()
+                .zip(&next.paddles)
+                .map(|(a, b)| BroadcastPaddle {
+                    position: a.position.lerp(b.position, t),
+                    ..*b
+                })
+                .collect()
+        } else {
+            next.paddles.clone()
+        };
+        let obstacles = if prev.obstacles.len() == next.obstacles.len() {
+            prev.obstacles
+                .iter()
+                .zip(&next.obstacles)
+                .map(|(a, b)| (a.0.lerp(b.0, t), a.1.lerp(b.1, t)))
+                .collect()
+        } else {
+            next.obstacles.clone()
+        };
+        Some(SpectatedFrame {
+            time,
+            balls,
+            paddles,
+            obstacles,
+        })
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn start_spectating(mut commands: Commands, target: Res<SpectateTarget>) {
+    let addr = target
+        .0
+        .unwrap_or(SocketAddr::from(([127, 0, 0, 1], BROADCAST_PORT)));
+    commands.insert_resource(Spectator::new(addr));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn stop_spectating(
+    mut commands: Commands,
+    spectator: Option<ResMut<Spectator>>,
+    mut config: ConfigMut<ArenaConfig>,
+) {
+    if let Some(live_arena) = spectator.and_then(|mut x| x.live_arena.take()) {
+        *r!(config.get_mut()) = live_arena;
+    }
+    commands.remove_resource::<Spectator>();
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn connect_to_broadcast(time: Res<Time>, mut spectator: ResMut<Spectator>) {
+    rq!(spectator.stream.is_none());
+    let Some(task) = spectator.connecting.as_mut() else {
+        rq!(spectator.retry.tick(time.delta()).just_finished());
+        // Connect in the background, so the window doesn't freeze while there's no match.
+        let addr = spectator.addr;
+        spectator.connecting =
+            Some(IoTaskPool::get().spawn(async move {
+                TcpStream::connect_timeout(&addr, Duration::from_millis(200))
+            }));
+        return;
+    };
+
+    let result = rq!(future::block_on(future::poll_once(task)));
+    spectator.connecting = None;
+    // Try again on the next retry if there's no match to watch yet.
+    let stream = rq!(result);
+    r!(stream.set_nonblocking(true));
+    info!("Spectating the match at {}", spectator.addr);
+    spectator.partial.clear();
+    spectator.frames.clear();
+    spectator.stream = Some(stream);
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn receive_broadcast(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spectator: ResMut<Spectator>,
+    mut config: ConfigMut<ArenaConfig>,
+    layout_config: ConfigRef<LayoutConfig>,
+    entity_query: Query<Entity, With<IsSpectated>>,
+) {
+    let lines = match spectator.read_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            info!("Lost the broadcast: {e}");
+            spectator.stream = None;
+            return;
+        },
+    };
+
+    let now = time.elapsed_secs();
+    for line in lines {
+        match c!(ron::from_str::<BroadcastMessage>(&line)) {
+            BroadcastMessage::Setup {
+                arena,
+                layout,
+                names,
+            } => {
+                spectator.scores = vec![0; names.len()];
+                spectator.names = names;
+                spectator.frames.clear();
+                for entity in &entity_query {
+                    commands.entity(entity).despawn();
+                }
+                let obstacles = layout_config
+                    .get()
+                    .and_then(|x| x.layouts.get(layout))
+                    .map_or(&[][..], |x| x.obstacles.as_slice());
+                commands.spawn_batch(spectated_arena(&arena, obstacles));
+
+                // Show the broadcast's arena, sized to fit the window.
+                let config = c!(config.get_mut());
+                let live_arena = std::mem::replace(config, arena);
+                spectator.live_arena.get_or_insert(live_arena);
+            },
+            BroadcastMessage::Frame {
+                balls,
+                paddles,
+                obstacles,
+                scores,
+                events,
+            } => {
+                spectator.scores = scores;
+                if let Some(&event) = events.last() {
+                    spectator.last_event = Some(event);
+                }
+                spectator.frames.push_back(SpectatedFrame {
+                    time: now,
+                    balls,
+                    paddles,
+                    obstacles,
+                });
+            },
+        }
+    }
+
+    // Keep only the frames that are still needed for interpolation.
+    let render_time = now - INTERPOLATION_DELAY;
+    while spectator.frames.len() > 2 && spectator.frames[1].time <= render_time {
+        spectator.frames.pop_front();
+    }
+}
+
+/// A sprite that shows part of the spectated match.
+#[derive(Component, Copy, Clone)]
+enum IsSpectated {
+    Boundary,
+    Paddle(usize),
+    Obstacle(usize),
+    Ball,
+}
+
+fn spectated_arena(config: &ArenaConfig, obstacles: &[Obstacle]) -> Vec<impl Bundle> {
+    let wall_color = Srgba::from_vec3(Vec3::new(0.5, 0.25, 0.25));
+
+    let mut bundles = vec![];
+    for boundary in Boundary::ALL {
+        let rect = boundary.rect(config.arena_size, config.boundary_width);
+        bundles.push((
+            Name::new(format!("Boundary{boundary:?}")),
+            Sprite::from_color(wall_color, rect.size()),
+            Transform::from_translation(rect.center().extend(0.0)),
+            IsSpectated::Boundary,
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+    for (i, obstacle) in obstacles.iter().enumerate() {
+        bundles.push((
+            Name::new(format!("Obstacle{i}")),
+            Sprite::from_color(wall_color, obstacle.shape.size()),
+            Transform::from_translation(obstacle.position.extend(0.0)),
+            IsSpectated::Obstacle(i),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+    bundles
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn draw_spectated_match(
+    mut commands: Commands,
+    time: Res<Time>,
+    spectator: Res<Spectator>,
+    config: ConfigRef<ArenaConfig>,
+    mut sprite_query: Query<(Entity, &IsSpectated, &mut Transform, &mut Sprite)>,
+) {
+    let config = r!(config.get());
+    let frame = rq!(spectator.sample(time.elapsed_secs() - INTERPOLATION_DELAY));
+    let color = Srgba::from_vec3(Vec3::splat(0.5));
+
+    let mut balls = frame.balls.into_iter();
+    let mut paddle_count = 0;
+    for (entity, &spectated, mut transform, mut sprite) in &mut sprite_query {
+        match spectated {
+            IsSpectated::Boundary => {},
+            // Paddles can change size with power-ups.
+            IsSpectated::Paddle(i) => match frame.paddles.get(i) {
+                Some(paddle) => {
+                    paddle_count = paddle_count.max(i + 1);
+                    transform.translation = paddle.position.extend(0.0);
+                    sprite.custom_size = Some(paddle.size);
+                },
+                None => commands.entity(entity).despawn(),
+            },
+            IsSpectated::Obstacle(i) => {
+                let &(position, angle) = cq!(frame.obstacles.get(i));
+                transform.translation = position.extend(0.0);
+                transform.rotation = Quat::from_rotation_z(angle);
+            },
+            IsSpectated::Ball => match balls.next() {
+                Some(position) => transform.translation = position.extend(0.0),
+                None => commands.entity(entity).despawn(),
+            },
+        }
+    }
+
+    for (i, paddle) in frame.paddles.iter().enumerate().skip(paddle_count) {
+        commands.spawn((
+            Name::new(format!("{:?}{:?}Paddle", paddle.side, paddle.slot)),
+            Sprite::from_color(color, paddle.size),
+            Transform::from_translation(paddle.position.extend(0.0)),
+            IsSpectated::Paddle(i),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+
+    let size = Vec2::splat(config.ball_radius * 2.0);
+    for position in balls {
+        commands.spawn((
+            Name::new("Ball"),
+            Sprite::from_color(color, size),
+            Transform::from_translation(position.extend(0.0)),
+            IsSpectated::Ball,
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_spectate_screen(mut commands: Commands, screen_root: Res<ScreenRoot>) {
+    commands.entity(screen_root.ui).with_child((
+        Name::new("SpectateOverlay"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceBetween,
+            padding: UiRect::all(Vw(2.0)),
+            ..Node::DEFAULT.full_size().abs()
+        },
+        Pickable::IGNORE,
+        children![
+            (widget::big_label(""), IsSpectateScore),
+            (
+                Name::new("Controls"),
+                Node::COLUMN_CENTER,
+                children![
+                    (widget::label(""), IsSpectateStatus),
+                    widget::row_of_buttons(children![widget::button(
+                        "Stop watching",
+                        stop_watching
+                    )]),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn stop_watching(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.spawn(fade_out(Screen::Title));
+}
+
+/// The label that shows the player names and score.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsSpectateScore;
+
+impl Configure for IsSpectateScore {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// The label that shows the connection status and the latest event.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsSpectateStatus;
+
+impl Configure for IsSpectateStatus {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_spectate_labels(
+    spectator: Res<Spectator>,
+    mut score_query: Query<&mut RichText, (With<IsSpectateScore>, Without<IsSpectateStatus>)>,
+    mut status_query: Query<&mut RichText, (With<IsSpectateStatus>, Without<IsSpectateScore>)>,
+) {
+    let score = match (spectator.names.as_slice(), spectator.scores.as_slice()) {
+        ([(_, left), (_, right)], [left_score, right_score]) => {
+            format!("{left}  [b]{left_score} - {right_score}[r]  {right}")
+        },
+        // Free-for-all matches show each side's lives.
+        (names, scores) => names
+            .iter()
+            .zip(scores)
+            .map(|((_, name), value)| format!("{name} [b]{value}[r]"))
+            .collect::<Vec<_>>()
+            .join("   "),
+    };
+    for mut label in &mut score_query {
+        label.sections = parse_rich(&score);
+    }
+
+    let name = |side: Side| {
+        spectator
+            .names
+            .iter()
+            .find(|(x, _)| *x == side)
+            .map_or(side.player_name(), |(_, name)| name.as_str())
+    };
+    let status = if spectator.stream.is_none() {
+        format!("Waiting for a match at {}...", spectator.addr)
+    } else {
+        match spectator.last_event {
+            Some(BroadcastEvent::Goal { scorer }) => format!("Goal for {}!", name(scorer)),
+            Some(BroadcastEvent::MatchWon { winner }) => format!("[b]{} wins!", name(winner)),
+            Some(BroadcastEvent::Serve) => "Serve".to_string(),
+            Some(BroadcastEvent::PaddleHit { .. } | BroadcastEvent::WallHit) | None => {
+                "[b]LIVE".to_string()
+            },
+        }
+    };
+    for mut label in &mut status_query {
+        label.sections = parse_rich(&status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_spectate_target_from_args() {
+        let local = SocketAddr::from(([127, 0, 0, 1], BROADCAST_PORT));
+        assert_eq!(SpectateTarget::from_args(args(&["pong"])).0, None);
+        assert_eq!(
+            SpectateTarget::from_args(args(&["pong", "--spectate"])).0,
+            Some(local),
+        );
+        assert_eq!(
+            SpectateTarget::from_args(args(&["pong", "--spectate", "10.0.0.2"])).0,
+            Some(SocketAddr::from(([10, 0, 0, 2], BROADCAST_PORT))),
+        );
+        assert_eq!(
+            SpectateTarget::from_args(args(&["pong", "--spectate", "10.0.0.2:9000"])).0,
+            Some(SocketAddr::from(([10, 0, 0, 2], 9000))),
+        );
+    }
+}
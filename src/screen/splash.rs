@@ -7,6 +7,7 @@ use crate::screen::Screen;
 use crate::screen::ScreenRoot;
 use crate::screen::fade::FADE_IN_SECS;
 use crate::screen::fade::fade_out;
+use crate::screen::spectate::SpectateTarget;
 use crate::screen::title::TitleAssets;
 use crate::screen::wait_in_screen;
 
@@ -54,6 +55,7 @@ fn update_splash(
     mut commands: Commands,
     progress: Res<ProgressTracker<BevyState<Screen>>>,
     frame: Res<FrameCount>,
+    spectate_target: Res<SpectateTarget>,
     mut last_done: Local<u32>,
 ) {
     let Progress { done, total } = progress.get_global_combined_progress();
@@ -64,7 +66,11 @@ fn update_splash(
 
     // Continue to the next screen when ready.
     if done == total {
-        commands.spawn(fade_out(Screen::Title));
+        commands.spawn(fade_out(if spectate_target.0.is_some() {
+            Screen::Spectate
+        } else {
+            Screen::Title
+        }));
     }
 
     info!("[Frame {}] Booting: {done} / {total}", frame.0);
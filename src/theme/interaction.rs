@@ -5,6 +5,7 @@ use bevy::reflect::Typed;
 use crate::animation::offset::NodeOffset;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::ui_audio;
+use crate::core::rng::UiRng;
 use crate::prelude::*;
 use crate::theme::ThemeAssets;
 
@@ -170,13 +171,18 @@ fn play_hover_sfx(
     audio_settings: Res<AudioSettings>,
     assets: Res<ThemeAssets>,
     sfx_query: Query<Option<&InteractionDisabled>, With<InteractionSfx>>,
+    mut ui_rng: ResMut<UiRng>,
     mut commands: Commands,
 ) {
     let target = r!(trigger.get_target());
     let disabled = rq!(sfx_query.get(target));
     rq!(!matches!(disabled, Some(InteractionDisabled(true))));
 
-    commands.spawn(ui_audio(&audio_settings, assets.sfx_hover.clone()));
+    commands.spawn(ui_audio(
+        &audio_settings,
+        assets.sfx_hover.clone(),
+        &mut ui_rng.0,
+    ));
 }
 
 fn play_click_sfx(
@@ -184,11 +190,16 @@ fn play_click_sfx(
     audio_settings: Res<AudioSettings>,
     assets: Res<ThemeAssets>,
     sfx_query: Query<Option<&InteractionDisabled>, With<InteractionSfx>>,
+    mut ui_rng: ResMut<UiRng>,
     mut commands: Commands,
 ) {
     let target = r!(trigger.get_target());
     let disabled = rq!(sfx_query.get(target));
     rq!(!matches!(disabled, Some(InteractionDisabled(true))));
 
-    commands.spawn(ui_audio(&audio_settings, assets.sfx_click.clone()));
+    commands.spawn(ui_audio(
+        &audio_settings,
+        assets.sfx_click.clone(),
+        &mut ui_rng.0,
+    ));
 }
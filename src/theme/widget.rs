@@ -128,6 +128,15 @@ where
     button_base(Vw(3.0), Vw(4.0), Vw(3.0), text, action)
 }
 
+pub fn slim_button<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
+where
+    E: Event,
+    B: Bundle,
+    I: Sync + IntoObserverSystem<E, B, M>,
+{
+    button_base(Vw(24.0), Vw(4.0), Vw(2.5), text, action)
+}
+
 pub fn button<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
     E: Event,
@@ -46,6 +46,11 @@ impl<C: Config> Configure for ConfigHandle<C> {
 
 #[cfg_attr(feature = "native_dev", hot)]
 fn load_config<C: Config>(world: &mut World) {
+    // Keep a config that was provided directly instead of loaded from a file.
+    if world.contains_resource::<ConfigHandle<C>>() {
+        return;
+    }
+
     let asset_server = r!(world.get_resource_mut::<AssetServer>());
     let handle = asset_server.load(format!("{}/{}", C::FOLDER, C::FILE));
     world.insert_resource(ConfigHandle::<C>(handle));
@@ -27,3 +27,15 @@ use crate::prelude::*;
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((late_commands::plugin, selection::plugin));
 }
+
+/// This app's folder in the platform's local config directory, created if necessary.
+pub fn config_local_dir() -> Option<std::path::PathBuf> {
+    #[cfg(feature = "native")]
+    return {
+        let path = dirs::config_local_dir()?.join(env!("CARGO_PKG_NAME"));
+        std::fs::create_dir_all(&path).ok()?;
+        Some(path)
+    };
+    #[cfg(not(feature = "native"))]
+    None
+}